@@ -2,33 +2,31 @@
 
 #![allow(non_upper_case_globals)]
 
-use itertools::Itertools;
-
-use std::io::{BufRead, BufReader, Read};
+use std::io::Read;
 
+use day01::{CalorieAggregator, LoadError};
 use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
 #[folder = "."]
 struct Asset;
 
-fn get_max_calories(reader: impl Read) -> usize {
-    BufReader::new(reader)
-        .lines()
-        .map(|line| line.unwrap().parse::<usize>().ok())
-        .batching(|it| {
-            it.take_while(|it| it.is_some())
-                .map(|maybe_num: Option<usize>| maybe_num.unwrap())
-                .sum1()
-        })
-        .max()
-        .unwrap()
+/// Returns the index of the elf carrying the most calories and the calories,
+/// or `None` if there are no elves at all
+fn get_max_calories(reader: impl Read) -> Result<Option<(usize, usize)>, LoadError> {
+    let mut aggregator = CalorieAggregator::new(1);
+    aggregator.feed(reader)?;
+    Ok(aggregator.max())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let asset = Asset::get("input.txt").unwrap();
-    let max_elf_calories: usize = get_max_calories(asset.data.as_ref());
-    println!("Max calories per elf: {}", max_elf_calories);
+    match get_max_calories(asset.data.as_ref())? {
+        Some((elf_index, max_elf_calories)) => {
+            println!("Max calories per elf: {max_elf_calories} (elf #{elf_index})")
+        }
+        None => println!("No elves found"),
+    }
     Ok(())
 }
 
@@ -39,12 +37,22 @@ mod tests {
     #[test]
     fn test_input() {
         let asset = Asset::get("test_input.txt").unwrap();
-        assert_eq!(get_max_calories(asset.data.as_ref()), 24000);
+        assert_eq!(
+            get_max_calories(asset.data.as_ref()).unwrap(),
+            Some((3, 24000))
+        );
     }
 
     #[test]
     fn actual_input() {
         let asset = Asset::get("input.txt").unwrap();
-        assert_eq!(get_max_calories(asset.data.as_ref()), 72511);
+        let (_, max_elf_calories) = get_max_calories(asset.data.as_ref()).unwrap().unwrap();
+        assert_eq!(max_elf_calories, 72511);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(get_max_calories("".as_bytes()).unwrap(), None);
+        assert_eq!(get_max_calories("\n\n".as_bytes()).unwrap(), None);
     }
 }
@@ -0,0 +1,560 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read},
+};
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// 1-based line number and the line itself
+    InvalidLine(usize, String),
+    Json(serde_json::Error),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "Error reading input: {err}"),
+            LoadError::InvalidLine(line_number, line) => {
+                write!(f, "Invalid line {line_number}: '{line}'")
+            }
+            LoadError::Json(err) => write!(f, "Invalid JSON input: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(value: io::Error) -> Self {
+        LoadError::Io(value)
+    }
+}
+
+/// Supported layouts of calorie lists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputFormat {
+    /// One number per line, groups are separated by lines equal to `separator`
+    /// (surrounding whitespace is ignored). With the default empty separator
+    /// groups are separated by blank lines, otherwise blank lines are skipped.
+    Plain { separator: String },
+    /// `elf,item` rows with an optional `elf,item` header. Items of the same elf
+    /// do not have to be adjacent, elves are ordered by their first appearance.
+    Csv,
+    /// Array of arrays of numbers, like `[[1000, 2000], [4000]]`
+    Json,
+}
+
+impl Default for InputFormat {
+    fn default() -> Self {
+        InputFormat::Plain {
+            separator: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlainLine {
+    Item(usize),
+    Separator,
+    Skip,
+}
+
+impl PlainLine {
+    /// Returns `None` for lines that are neither a number nor a separator
+    pub fn classify(line: &str, separator: &str) -> Option<PlainLine> {
+        let line = line.trim();
+        if line == separator.trim() {
+            Some(PlainLine::Separator)
+        } else if line.is_empty() {
+            Some(PlainLine::Skip)
+        } else {
+            line.parse().ok().map(PlainLine::Item)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// 0-based position of the elf in the input
+    pub index: usize,
+    /// Name given in the input, only the CSV format has names
+    pub name: Option<String>,
+    /// Calories of every item carried
+    pub items: Vec<usize>,
+}
+
+impl Elf {
+    pub fn total_calories(&self) -> usize {
+        self.items.iter().sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// Inclusive lower bound of total calories
+    pub start: usize,
+    /// Exclusive upper bound of total calories
+    pub end: usize,
+    pub count: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Inventory {
+    pub elves: Vec<Elf>,
+}
+
+impl Inventory {
+    /// Groups without items are skipped in every format
+    fn from_groups(groups: impl IntoIterator<Item = (Option<String>, Vec<usize>)>) -> Inventory {
+        let elves = groups
+            .into_iter()
+            .filter(|(_, items)| !items.is_empty())
+            .enumerate()
+            .map(|(index, (name, items))| Elf { index, name, items })
+            .collect();
+        Inventory { elves }
+    }
+
+    fn from_unnamed_groups(groups: Vec<Vec<usize>>) -> Inventory {
+        Inventory::from_groups(groups.into_iter().map(|items| (None, items)))
+    }
+
+    /// Parses groups of numbers, one per line, separated by blank lines:
+    /// 1000
+    /// 2000
+    ///
+    /// 4000
+    pub fn parse(reader: impl Read) -> Result<Inventory, LoadError> {
+        Inventory::load(reader, &InputFormat::default())
+    }
+
+    pub fn load(reader: impl Read, format: &InputFormat) -> Result<Inventory, LoadError> {
+        match format {
+            InputFormat::Plain { separator } => Inventory::load_plain(reader, separator),
+            InputFormat::Csv => Inventory::load_csv(reader),
+            InputFormat::Json => serde_json::from_reader(reader)
+                .map(Inventory::from_unnamed_groups)
+                .map_err(LoadError::Json),
+        }
+    }
+
+    fn load_plain(reader: impl Read, separator: &str) -> Result<Inventory, LoadError> {
+        let mut groups = vec![];
+        let mut items = vec![];
+        for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            match PlainLine::classify(&line, separator) {
+                Some(PlainLine::Item(calories)) => items.push(calories),
+                Some(PlainLine::Separator) => {
+                    if !items.is_empty() {
+                        groups.push(std::mem::take(&mut items));
+                    }
+                }
+                Some(PlainLine::Skip) => {}
+                None => return Err(LoadError::InvalidLine(line_idx + 1, line)),
+            }
+        }
+        if !items.is_empty() {
+            groups.push(items);
+        }
+        Ok(Inventory::from_unnamed_groups(groups))
+    }
+
+    fn load_csv(reader: impl Read) -> Result<Inventory, LoadError> {
+        let mut groups: Vec<(Option<String>, Vec<usize>)> = vec![];
+        let mut group_of_elf: HashMap<String, usize> = HashMap::new();
+        for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty()
+                || (line_idx == 0 && line.trim().eq_ignore_ascii_case("elf,item"))
+            {
+                continue;
+            }
+            let (elf, item) = match line.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+                [elf, item] if !elf.is_empty() => match item.parse::<usize>() {
+                    Ok(item) => (elf.to_owned(), item),
+                    Err(_) => return Err(LoadError::InvalidLine(line_idx + 1, line)),
+                },
+                _ => return Err(LoadError::InvalidLine(line_idx + 1, line)),
+            };
+            let group_idx = *group_of_elf.entry(elf.clone()).or_insert_with(|| {
+                groups.push((Some(elf), vec![]));
+                groups.len() - 1
+            });
+            groups[group_idx].1.push(item);
+        }
+        Ok(Inventory::from_groups(groups))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    /// Total calories of every elf, in the input order
+    pub fn totals(&self) -> Vec<usize> {
+        self.elves.iter().map(Elf::total_calories).collect()
+    }
+
+    /// Elves carrying the most calories, the largest first.
+    /// Elves with equal totals keep their input order.
+    pub fn top(&self, k: usize) -> Vec<&Elf> {
+        let mut ranked: Vec<&Elf> = self.elves.iter().collect();
+        ranked.sort_by_key(|elf| std::cmp::Reverse(elf.total_calories()));
+        ranked.truncate(k);
+        ranked
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.totals().iter().sum::<usize>() as f64 / self.elves.len() as f64)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+
+    /// Percentile of total calories in `0.0..=100.0`,
+    /// linearly interpolated between the closest ranks.
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "Percentile is expected to be within 0..=100, but was {p}"
+        );
+        if self.is_empty() {
+            return None;
+        }
+        let mut totals = self.totals();
+        totals.sort_unstable();
+
+        let rank = p / 100.0 * (totals.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let fraction = rank - lower as f64;
+        Some(totals[lower] as f64 + (totals[upper] as f64 - totals[lower] as f64) * fraction)
+    }
+
+    /// Counts elves by total calories in buckets of `bucket_width`, from the bucket
+    /// of the smallest total to the bucket of the largest one, including empty buckets.
+    pub fn histogram(&self, bucket_width: usize) -> Vec<HistogramBucket> {
+        assert!(bucket_width > 0, "Bucket width must be positive");
+        let totals = self.totals();
+        let (min, max) = match (totals.iter().min(), totals.iter().max()) {
+            (Some(&min), Some(&max)) => (min / bucket_width, max / bucket_width),
+            _ => return vec![],
+        };
+        let mut buckets: Vec<HistogramBucket> = (min..=max)
+            .map(|bucket| HistogramBucket {
+                start: bucket * bucket_width,
+                end: (bucket + 1) * bucket_width,
+                count: 0,
+            })
+            .collect();
+        for total in totals {
+            buckets[total / bucket_width - min].count += 1;
+        }
+        buckets
+    }
+}
+
+/// Incrementally aggregates calorie groups as they arrive, e.g. line by line from stdin,
+/// without keeping all of them in memory. Only the `top_k` largest groups are retained.
+///
+/// All queries take the group that is still being filled into account as well.
+#[derive(Debug)]
+pub struct CalorieAggregator {
+    top_k: usize,
+    // Reverse allows to efficiently remove the smallest value form the "top+1" - sized max heap.
+    // Among equal totals the elf that came later is considered smaller, so it is removed first.
+    heap: BinaryHeap<Reverse<(usize, Reverse<usize>)>>,
+    /// Largest completed group as `(elf index, total calories)`
+    max: Option<(usize, usize)>,
+    completed: usize,
+    total: usize,
+    current: Option<usize>,
+    separator: String,
+    lines_fed: usize,
+}
+
+impl CalorieAggregator {
+    /// Groups are separated by blank lines
+    pub fn new(top_k: usize) -> Self {
+        CalorieAggregator::with_separator(top_k, "")
+    }
+
+    /// Groups are separated by lines equal to `separator`, see [InputFormat::Plain]
+    pub fn with_separator(top_k: usize, separator: &str) -> Self {
+        CalorieAggregator {
+            top_k,
+            heap: BinaryHeap::with_capacity(top_k + 1),
+            max: None,
+            completed: 0,
+            total: 0,
+            current: None,
+            separator: separator.to_owned(),
+            lines_fed: 0,
+        }
+    }
+
+    pub fn push_item(&mut self, calories: usize) {
+        *self.current.get_or_insert(0) += calories;
+        self.total += calories;
+    }
+
+    /// Closes the current group. Does nothing if no items were pushed since the last break.
+    pub fn end_group(&mut self) {
+        let Some(group_total) = self.current.take() else {
+            return;
+        };
+        let elf_index = self.completed;
+        self.completed += 1;
+
+        if self.max.is_none_or(|(_, max)| group_total > max) {
+            self.max = Some((elf_index, group_total));
+        }
+        self.heap.push(Reverse((group_total, Reverse(elf_index))));
+        if self.heap.len() > self.top_k {
+            self.heap.pop();
+        }
+    }
+
+    /// A number is an item and a separator is a group break, anything else is an error
+    /// (and does not affect the aggregated values).
+    pub fn feed_line(&mut self, line: &str) -> Result<(), LoadError> {
+        self.lines_fed += 1;
+        match PlainLine::classify(line, &self.separator) {
+            Some(PlainLine::Item(calories)) => self.push_item(calories),
+            Some(PlainLine::Separator) => self.end_group(),
+            Some(PlainLine::Skip) => {}
+            None => return Err(LoadError::InvalidLine(self.lines_fed, line.to_owned())),
+        }
+        Ok(())
+    }
+
+    /// Feeds all lines, stopping at the first invalid one
+    pub fn feed(&mut self, reader: impl Read) -> Result<(), LoadError> {
+        for line in BufReader::new(reader).lines() {
+            self.feed_line(&line?)?;
+        }
+        Ok(())
+    }
+
+    /// Number of groups seen so far
+    pub fn elves(&self) -> usize {
+        self.completed + usize::from(self.current.is_some())
+    }
+
+    /// Total calories of all groups
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The largest group as `(elf index, total calories)`, if there were any groups at all
+    pub fn max(&self) -> Option<(usize, usize)> {
+        match (self.max, self.current) {
+            (Some((_, max)), Some(current)) if current > max => Some((self.completed, current)),
+            (None, Some(current)) => Some((self.completed, current)),
+            (max, _) => max,
+        }
+    }
+
+    /// Up to `top_k` largest groups as `(elf index, total calories)`, the largest first
+    pub fn top(&self) -> Vec<(usize, usize)> {
+        let mut top: Vec<(usize, usize)> = self
+            .heap
+            .iter()
+            .map(|Reverse((total, Reverse(elf_index)))| (*elf_index, *total))
+            .chain(self.current.map(|current| (self.completed, current)))
+            .collect();
+        top.sort_by_key(|&(elf_index, total)| (Reverse(total), elf_index));
+        top.truncate(self.top_k);
+        top
+    }
+
+    /// Sum of the `top_k` largest groups
+    pub fn top_sum(&self) -> usize {
+        self.top().iter().map(|(_, total)| total).sum()
+    }
+}
+
+#[allow(non_upper_case_globals)]
+#[cfg(test)]
+mod tests {
+    use rust_embed::RustEmbed;
+
+    use super::*;
+
+    #[derive(RustEmbed)]
+    #[folder = "."]
+    struct Asset;
+
+    fn test_inventory() -> Inventory {
+        let asset = Asset::get("test_input.txt").unwrap();
+        Inventory::parse(asset.data.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn parse_keeps_elves_and_items() {
+        let inventory = test_inventory();
+        assert_eq!(inventory.elves.len(), 5);
+        assert_eq!(
+            inventory.elves[0],
+            Elf {
+                index: 0,
+                name: None,
+                items: vec![1000, 2000, 3000]
+            }
+        );
+        assert_eq!(inventory.totals(), vec![6000, 4000, 11000, 24000, 10000]);
+    }
+
+    #[test]
+    fn top_elves() {
+        let inventory = test_inventory();
+        let top: Vec<(usize, usize)> = inventory
+            .top(3)
+            .iter()
+            .map(|elf| (elf.index, elf.total_calories()))
+            .collect();
+        assert_eq!(top, vec![(3, 24000), (2, 11000), (4, 10000)]);
+        assert_eq!(inventory.top(10).len(), 5);
+    }
+
+    #[test]
+    fn statistics() {
+        let inventory = test_inventory();
+        assert_eq!(inventory.mean(), Some(11000.0));
+        assert_eq!(inventory.median(), Some(10000.0));
+        assert_eq!(inventory.percentile(0.0), Some(4000.0));
+        assert_eq!(inventory.percentile(100.0), Some(24000.0));
+        assert_eq!(inventory.percentile(25.0), Some(6000.0));
+        assert_eq!(inventory.percentile(12.5), Some(5000.0));
+    }
+
+    #[test]
+    fn histogram() {
+        let inventory = test_inventory();
+        let counts: Vec<(usize, usize)> = inventory
+            .histogram(10000)
+            .iter()
+            .map(|bucket| (bucket.start, bucket.count))
+            .collect();
+        assert_eq!(counts, vec![(0, 2), (10000, 2), (20000, 1)]);
+    }
+
+    #[test]
+    fn empty_inventory() {
+        let inventory = Inventory::parse("".as_bytes()).unwrap();
+        assert!(inventory.is_empty());
+        assert_eq!(inventory.mean(), None);
+        assert_eq!(inventory.median(), None);
+        assert!(inventory.top(3).is_empty());
+        assert!(inventory.histogram(1000).is_empty());
+    }
+
+    #[test]
+    fn aggregator_matches_inventory() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        let mut aggregator = CalorieAggregator::new(3);
+        aggregator.feed(asset.data.as_ref()).unwrap();
+        assert_eq!(aggregator.elves(), 5);
+        assert_eq!(aggregator.total(), 55000);
+        assert_eq!(aggregator.max(), Some((3, 24000)));
+        assert_eq!(aggregator.top(), vec![(3, 24000), (2, 11000), (4, 10000)]);
+        assert_eq!(aggregator.top_sum(), 45000);
+    }
+
+    #[test]
+    fn aggregator_answers_at_any_moment() {
+        let mut aggregator = CalorieAggregator::new(2);
+        assert_eq!(aggregator.max(), None);
+        assert_eq!(aggregator.top(), vec![]);
+        assert_eq!(aggregator.total(), 0);
+
+        aggregator.push_item(100);
+        assert_eq!(aggregator.max(), Some((0, 100)));
+        aggregator.end_group();
+        aggregator.end_group();
+
+        aggregator.push_item(50);
+        assert_eq!(aggregator.top(), vec![(0, 100), (1, 50)]);
+        aggregator.push_item(60);
+        assert_eq!(aggregator.max(), Some((1, 110)));
+        assert_eq!(aggregator.top(), vec![(1, 110), (0, 100)]);
+        aggregator.end_group();
+
+        aggregator.push_item(100);
+        assert_eq!(aggregator.top(), vec![(1, 110), (0, 100)]);
+        assert_eq!(aggregator.elves(), 3);
+        assert_eq!(aggregator.total(), 310);
+    }
+
+    #[test]
+    fn invalid_lines_are_reported() {
+        let err = Inventory::parse("1000\n\n2000\nabc\n3000".as_bytes()).unwrap_err();
+        assert!(matches!(err, LoadError::InvalidLine(4, line) if line == "abc"));
+
+        let mut aggregator = CalorieAggregator::new(1);
+        assert!(aggregator.feed_line("100").is_ok());
+        let err = aggregator.feed_line("1OO").unwrap_err();
+        assert!(matches!(err, LoadError::InvalidLine(2, _)));
+        assert_eq!(aggregator.max(), Some((0, 100)));
+    }
+
+    #[test]
+    fn custom_separator() {
+        let format = InputFormat::Plain {
+            separator: "---".into(),
+        };
+        let input = "1000\n2000\n---\n\n4000\n---\n---\n5000";
+        let inventory = Inventory::load(input.as_bytes(), &format).unwrap();
+        assert_eq!(inventory.totals(), vec![3000, 4000, 5000]);
+
+        let mut aggregator = CalorieAggregator::with_separator(2, "---");
+        aggregator.feed(input.as_bytes()).unwrap();
+        assert_eq!(aggregator.top(), vec![(2, 5000), (1, 4000)]);
+    }
+
+    #[test]
+    fn csv_format() {
+        let input = "elf,item\nalice,1000\nbob,4000\nalice,2000\n\ncarol,500";
+        let inventory = Inventory::load(input.as_bytes(), &InputFormat::Csv).unwrap();
+        assert_eq!(inventory.totals(), vec![3000, 4000, 500]);
+        assert_eq!(inventory.elves[0].items, vec![1000, 2000]);
+        let names: Vec<_> = inventory
+            .elves
+            .iter()
+            .map(|elf| elf.name.as_deref())
+            .collect();
+        assert_eq!(names, vec![Some("alice"), Some("bob"), Some("carol")]);
+        assert_eq!(inventory.top(1)[0].name.as_deref(), Some("bob"));
+
+        let err = Inventory::load("alice,1000\nbob".as_bytes(), &InputFormat::Csv).unwrap_err();
+        assert!(matches!(err, LoadError::InvalidLine(2, _)));
+        let err = Inventory::load("alice,x".as_bytes(), &InputFormat::Csv).unwrap_err();
+        assert!(matches!(err, LoadError::InvalidLine(1, _)));
+    }
+
+    #[test]
+    fn json_format() {
+        let input = "[[1000, 2000, 3000], [4000], [5000, 6000]]";
+        let inventory = Inventory::load(input.as_bytes(), &InputFormat::Json).unwrap();
+        assert_eq!(inventory.totals(), vec![6000, 4000, 11000]);
+        assert!(inventory.elves.iter().all(|elf| elf.name.is_none()));
+
+        // empty groups are skipped, like blank-line runs in the plain format
+        let inventory = Inventory::load("[[], [1000], []]".as_bytes(), &InputFormat::Json).unwrap();
+        assert_eq!(inventory.totals(), vec![1000]);
+        assert_eq!(inventory.elves[0].index, 0);
+        assert!(Inventory::load("[[]]".as_bytes(), &InputFormat::Json)
+            .unwrap()
+            .is_empty());
+
+        let err = Inventory::load("[[1000, \"x\"]]".as_bytes(), &InputFormat::Json).unwrap_err();
+        assert!(matches!(err, LoadError::Json(_)));
+    }
+}
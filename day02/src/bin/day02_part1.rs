@@ -2,9 +2,9 @@
 
 #![allow(non_upper_case_globals)]
 
-use std::io::{BufRead, BufReader, Read};
+use std::io::Read;
 
-use day02::Round;
+use day02::Schema;
 use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
@@ -12,12 +12,7 @@ use rust_embed::RustEmbed;
 struct Asset;
 
 fn get_total_points(reader: impl Read) -> usize {
-    BufReader::new(reader)
-        .lines()
-        .map(|line_maybe| line_maybe.unwrap())
-        .map(|round_str| round_str.parse::<Round>().unwrap())
-        .map(|round| round.total_points())
-        .sum()
+    Schema::part1().total_points(reader).unwrap()
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -2,70 +2,17 @@
 
 #![allow(non_upper_case_globals)]
 
-use std::io::{BufRead, BufReader, Read};
-
-use day02::{Choice, Round};
+use std::io::Read;
 
+use day02::Schema;
 use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
 #[folder = "."]
 struct Asset;
 
-enum DesiredOutcome {
-    Lose,
-    Draw,
-    Win,
-}
-
-impl DesiredOutcome {
-    fn decode(c: char) -> DesiredOutcome {
-        match c {
-            'X' => DesiredOutcome::Lose,
-            'Y' => DesiredOutcome::Draw,
-            'Z' => DesiredOutcome::Win,
-            _ => panic!("unexpected desired behavior"),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct RoundParseError {
-    #[allow(dead_code)]
-    pub reason: String,
-}
-
-fn parse_round(s: &str) -> Result<(Choice, DesiredOutcome), RoundParseError> {
-    let (&enemy_s, &desired_outcome_s) = (
-        &s[..1].chars().next().ok_or(RoundParseError {
-            reason: "error reading the 1st char in a game round".into(),
-        })?,
-        &s[2..].chars().next().ok_or(RoundParseError {
-            reason: "error reading the 2nd char in a game round".into(),
-        })?,
-    );
-    Ok((
-        Choice::decode(enemy_s),
-        DesiredOutcome::decode(desired_outcome_s),
-    ))
-}
-
-fn choose_strategy(enemy: Choice, desired_outcome: DesiredOutcome) -> Round {
-    let own: Choice = match desired_outcome {
-        DesiredOutcome::Lose => enemy.wins(),
-        DesiredOutcome::Draw => enemy.clone(),
-        DesiredOutcome::Win => enemy.looses_to(),
-    };
-    Round::new(enemy, own)
-}
-
 fn total_strategic_points(reader: impl Read) -> usize {
-    BufReader::new(reader)
-        .lines()
-        .map(|round_str| parse_round(&round_str.unwrap()).unwrap())
-        .map(|(enemy, desired_outcome)| choose_strategy(enemy, desired_outcome))
-        .map(|round| round.total_points())
-        .sum()
+    Schema::part2().total_points(reader).unwrap()
 }
 
 fn main() {
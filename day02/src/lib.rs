@@ -1,47 +1,267 @@
-#![feature(lazy_cell)]
-
 use std::{
-    char::ParseCharError, cmp::Ordering, collections::HashMap, str::FromStr, sync::LazyLock,
+    char::ParseCharError,
+    collections::HashMap,
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read},
+    str::FromStr,
+    sync::LazyLock,
 };
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub fn points(&self) -> usize {
+        match self {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError {
+    Empty,
+    DuplicateChoice(String),
+    UnknownChoice(String),
+    BeatsItself(String),
+    /// Neither of the two choices beats the other one
+    Undecided(String, String),
+    /// Both choices beat each other
+    Contradiction(String, String),
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::Empty => write!(f, "A game needs at least one choice"),
+            GameError::DuplicateChoice(name) => write!(f, "Duplicate choice '{name}'"),
+            GameError::UnknownChoice(name) => write!(f, "Unknown choice '{name}'"),
+            GameError::BeatsItself(name) => write!(f, "Choice '{name}' cannot beat itself"),
+            GameError::Undecided(a, b) => write!(f, "Neither '{a}' nor '{b}' wins"),
+            GameError::Contradiction(a, b) => write!(f, "'{a}' and '{b}' beat each other"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+/// Cyclic-dominance game: every two distinct choices are in a "beats" relation
+/// in exactly one direction, i.e. the relation is a tournament.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    /// `beats[a][b]` is set if choice `a` beats choice `b`
+    beats: Vec<Vec<bool>>,
+}
+
+impl Game {
+    /// Builds a game out of choice names and `(winner, loser)` pairs.
+    /// Choices score their 1-based position in `names`.
+    pub fn new(names: &[&str], beats: &[(&str, &str)]) -> Result<Game, GameError> {
+        if names.is_empty() {
+            return Err(GameError::Empty);
+        }
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(GameError::DuplicateChoice(name.to_string()));
+            }
+        }
+        let index_of = |name: &str| {
+            names
+                .iter()
+                .position(|&n| n == name)
+                .ok_or_else(|| GameError::UnknownChoice(name.to_string()))
+        };
+
+        let mut matrix = vec![vec![false; names.len()]; names.len()];
+        for &(winner, loser) in beats {
+            matrix[index_of(winner)?][index_of(loser)?] = true;
+        }
+        let game = Game {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            beats: matrix,
+        };
+        game.validate()?;
+        Ok(game)
+    }
+
+    fn validate(&self) -> Result<(), GameError> {
+        for a in 0..self.names.len() {
+            if self.beats[a][a] {
+                return Err(GameError::BeatsItself(self.names[a].clone()));
+            }
+            for b in a + 1..self.names.len() {
+                let (a_name, b_name) = (self.names[a].clone(), self.names[b].clone());
+                match (self.beats[a][b], self.beats[b][a]) {
+                    (false, false) => return Err(GameError::Undecided(a_name, b_name)),
+                    (true, true) => return Err(GameError::Contradiction(a_name, b_name)),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn rock_paper_scissors() -> Game {
+        Game::new(
+            &["Rock", "Paper", "Scissors"],
+            &[
+                ("Rock", "Scissors"),
+                ("Scissors", "Paper"),
+                ("Paper", "Rock"),
+            ],
+        )
+        .unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Game {
+        Game::new(
+            &["Rock", "Paper", "Scissors", "Lizard", "Spock"],
+            &[
+                ("Scissors", "Paper"),
+                ("Paper", "Rock"),
+                ("Rock", "Lizard"),
+                ("Lizard", "Spock"),
+                ("Spock", "Scissors"),
+                ("Scissors", "Lizard"),
+                ("Lizard", "Paper"),
+                ("Paper", "Spock"),
+                ("Spock", "Rock"),
+                ("Rock", "Scissors"),
+            ],
+        )
+        .unwrap()
+    }
+
+    pub fn choice_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether every choice beats the same number of other choices
+    pub fn is_balanced(&self) -> bool {
+        let first = self.get(0).unwrap().defeated().count();
+        self.moves().all(|m| m.defeated().count() == first)
+    }
+
+    pub fn get(&self, index: usize) -> Option<Move<'_>> {
+        (index < self.names.len()).then_some(Move { game: self, index })
+    }
+
+    pub fn find(&self, name: &str) -> Option<Move<'_>> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .and_then(|index| self.get(index))
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move<'_>> {
+        (0..self.names.len()).map(move |index| Move { game: self, index })
+    }
+}
+
+/// Anything that can be played in a `Round`
+pub trait GameChoice {
+    /// Outcome of the round for the player choosing `self`
+    fn outcome(&self, other: &Self) -> Outcome;
+    /// Points for merely choosing `self`
+    fn points(&self) -> usize;
+}
+
+/// A choice within a particular `Game`
+#[derive(Debug, Clone, Copy)]
+pub struct Move<'g> {
+    game: &'g Game,
+    index: usize,
+}
+
+impl<'g> Move<'g> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn name(&self) -> &'g str {
+        &self.game.names[self.index]
+    }
+
+    pub fn beats(&self, other: &Move) -> bool {
+        self.game.beats[self.index][other.index]
+    }
+
+    /// Choices this one beats
+    pub fn defeated(&self) -> impl Iterator<Item = Move<'g>> + '_ {
+        self.game.moves().filter(|m| self.beats(m))
+    }
+
+    /// Choices that beat this one
+    pub fn defeating(&self) -> impl Iterator<Item = Move<'g>> + '_ {
+        self.game.moves().filter(|m| m.beats(self))
+    }
+}
+
+impl PartialEq for Move<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.game, other.game) && self.index == other.index
+    }
+}
+
+impl Eq for Move<'_> {}
+
+impl Display for Move<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl GameChoice for Move<'_> {
+    fn outcome(&self, other: &Self) -> Outcome {
+        if self.beats(other) {
+            Outcome::Win
+        } else if other.beats(self) {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    fn points(&self) -> usize {
+        self.index + 1
+    }
+}
+
+static ROCK_PAPER_SCISSORS: LazyLock<Game> = LazyLock::new(Game::rock_paper_scissors);
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Choice {
     Rock,
     Paper,
     Scissors,
 }
 
-static CHOICE_WINS: LazyLock<HashMap<Choice, Choice>> = LazyLock::new(|| {
-    let mut m = HashMap::with_capacity(3);
-    m.insert(Choice::Rock, Choice::Scissors);
-    m.insert(Choice::Scissors, Choice::Paper);
-    m.insert(Choice::Paper, Choice::Rock);
-    m
-});
+impl Choice {
+    pub const ALL: [Choice; 3] = [Choice::Rock, Choice::Paper, Choice::Scissors];
+
+    fn as_move(&self) -> Move<'static> {
+        ROCK_PAPER_SCISSORS.get(*self as usize).unwrap()
+    }
 
-static CHOICE_LOOSES: LazyLock<HashMap<Choice, Choice>> = LazyLock::new(|| {
-    let mut m = HashMap::with_capacity(3);
-    for (key, value) in CHOICE_WINS.iter() {
-        m.insert(value.clone(), key.clone());
+    fn from_move(m: Move) -> Choice {
+        Choice::ALL[m.index()]
     }
-    m
-});
 
-impl Choice {
+    /// The choice this one beats
     pub fn wins(&self) -> Choice {
-        CHOICE_WINS.get(self).unwrap().clone()
+        Choice::from_move(self.as_move().defeated().next().unwrap())
     }
 
+    /// The choice that beats this one
     pub fn looses_to(&self) -> Choice {
-        CHOICE_LOOSES.get(self).unwrap().clone()
-    }
-
-    pub fn points(&self) -> usize {
-        match self {
-            Choice::Rock => 1,
-            Choice::Paper => 2,
-            Choice::Scissors => 3,
-        }
+        Choice::from_move(self.as_move().defeating().next().unwrap())
     }
 
     pub fn decode(c: char) -> Choice {
@@ -54,48 +274,49 @@ impl Choice {
     }
 }
 
-impl PartialOrd for Choice {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self == other {
-            return Some(Ordering::Equal);
-        }
-        let what_self_wins = CHOICE_WINS.get(self).unwrap();
-        if what_self_wins == other {
-            Some(Ordering::Greater)
-        } else {
-            Some(Ordering::Less)
-        }
+impl GameChoice for Choice {
+    fn outcome(&self, other: &Self) -> Outcome {
+        self.as_move().outcome(&other.as_move())
+    }
+
+    fn points(&self) -> usize {
+        self.as_move().points()
     }
 }
 
 #[derive(Debug)]
-pub struct Round {
-    pub enemy: Choice,
-    pub own: Choice,
+pub struct Round<C = Choice> {
+    pub enemy: C,
+    pub own: C,
 }
 
-impl Round {
-    pub fn new(enemy: Choice, own: Choice) -> Self {
+impl<C: GameChoice> Round<C> {
+    pub fn new(enemy: C, own: C) -> Self {
         Round { enemy, own }
     }
 
+    pub fn outcome(&self) -> Outcome {
+        self.own.outcome(&self.enemy)
+    }
+
     pub fn total_points(&self) -> usize {
         // 1) unconditionally get points for the chozen strategy
-        let mut total = self.own.points();
-
         // 2) Get 0, 3, or 6 points depending on the round outcome
-        if self.own > self.enemy {
-            total += 6;
-        } else if self.enemy == self.own {
-            total += 3;
-        }
-
-        total
+        self.own.points() + self.outcome().points()
     }
 }
 
 #[derive(Debug)]
 pub struct ParseRoundError(String);
+
+impl Display for ParseRoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseRoundError {}
+
 impl From<ParseCharError> for ParseRoundError {
     fn from(value: ParseCharError) -> Self {
         ParseRoundError(format!("Error parsing round from '{}'", value))
@@ -110,3 +331,757 @@ impl FromStr for Round {
         Ok(Round::new(Choice::decode(enemy_s), Choice::decode(own_s)))
     }
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SchemaError {
+    Game(GameError),
+    InvalidLine(String),
+    UnknownKey(String),
+    InvalidEntry(String),
+    UnknownChoice(String),
+    UnknownOutcome(String),
+    MissingKey(&'static str),
+    UnknownSymbol(String),
+    /// A symbol defined twice in a column, e.g. under both `own` and `outcome`
+    DuplicateSymbol(String),
+    Io(io::ErrorKind),
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::Game(err) => write!(f, "Invalid game: {err}"),
+            SchemaError::InvalidLine(line) => write!(f, "Invalid line '{line}'"),
+            SchemaError::UnknownKey(key) => write!(f, "Unknown key '{key}'"),
+            SchemaError::InvalidEntry(entry) => write!(f, "Invalid entry '{entry}'"),
+            SchemaError::UnknownChoice(name) => write!(f, "Unknown choice '{name}'"),
+            SchemaError::UnknownOutcome(name) => write!(f, "Unknown outcome '{name}'"),
+            SchemaError::MissingKey(key) => write!(f, "Missing key '{key}'"),
+            SchemaError::UnknownSymbol(symbol) => write!(f, "Unknown symbol '{symbol}'"),
+            SchemaError::DuplicateSymbol(symbol) => write!(f, "Duplicate symbol '{symbol}'"),
+            SchemaError::Io(kind) => write!(f, "Error reading input: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl From<io::Error> for SchemaError {
+    fn from(value: io::Error) -> Self {
+        SchemaError::Io(value.kind())
+    }
+}
+
+impl From<GameError> for SchemaError {
+    fn from(value: GameError) -> Self {
+        SchemaError::Game(value)
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = SchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Lose" => Ok(Outcome::Lose),
+            "Draw" => Ok(Outcome::Draw),
+            "Win" => Ok(Outcome::Win),
+            _ => Err(SchemaError::UnknownOutcome(s.to_string())),
+        }
+    }
+}
+
+/// Meaning of a symbol in the second column of a strategy guide
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Response {
+    /// Index of the choice to play
+    Choice(usize),
+    /// Any choice leading to the outcome
+    Outcome(Outcome),
+}
+
+/// Describes how to decode and score a strategy guide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+    pub game: Game,
+    opponent: HashMap<String, usize>,
+    response: HashMap<String, Response>,
+    choice_points: Vec<usize>,
+    /// Indexed by `Outcome as usize`
+    outcome_points: [usize; 3],
+}
+
+impl Schema {
+    /// First column is the opponent choice, the second one is our own choice
+    pub const PART1: &'static str = "\
+choices: Rock Paper Scissors
+beats: Rock>Scissors Scissors>Paper Paper>Rock
+opponent: A=Rock B=Paper C=Scissors
+own: X=Rock Y=Paper Z=Scissors
+choice_points: Rock=1 Paper=2 Scissors=3
+outcome_points: Lose=0 Draw=3 Win=6
+";
+
+    /// First column is the opponent choice, the second one is how the round must end
+    pub const PART2: &'static str = "\
+choices: Rock Paper Scissors
+beats: Rock>Scissors Scissors>Paper Paper>Rock
+opponent: A=Rock B=Paper C=Scissors
+outcome: X=Lose Y=Draw Z=Win
+choice_points: Rock=1 Paper=2 Scissors=3
+outcome_points: Lose=0 Draw=3 Win=6
+";
+
+    pub fn part1() -> Schema {
+        Schema::parse(Schema::PART1).unwrap()
+    }
+
+    pub fn part2() -> Schema {
+        Schema::parse(Schema::PART2).unwrap()
+    }
+
+    /// Parses `key: entries` lines, see `Schema::PART1`. Blank lines and lines starting
+    /// with `#` are ignored. `choices` and `beats` define the game and default to Rock,
+    /// Paper, Scissors. Missing points default to `Move::points` and `Outcome::points`.
+    pub fn parse(config: &str) -> Result<Schema, SchemaError> {
+        let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+        for line in config.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, entries) = line
+                .split_once(':')
+                .ok_or_else(|| SchemaError::InvalidLine(line.to_string()))?;
+            let key = key.trim();
+            if ![
+                "choices",
+                "beats",
+                "opponent",
+                "own",
+                "outcome",
+                "choice_points",
+                "outcome_points",
+            ]
+            .contains(&key)
+            {
+                return Err(SchemaError::UnknownKey(key.to_string()));
+            }
+            sections
+                .entry(key)
+                .or_default()
+                .extend(entries.split_whitespace());
+        }
+        let section = |key: &str| sections.get(key).cloned().unwrap_or_default();
+        let pairs = |key: &str, separator: char| {
+            section(key)
+                .into_iter()
+                .map(|entry| {
+                    entry
+                        .split_once(separator)
+                        .ok_or_else(|| SchemaError::InvalidEntry(entry.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let game = match sections.get("choices") {
+            Some(choices) => Game::new(choices, &pairs("beats", '>')?)?,
+            None if sections.contains_key("beats") => {
+                return Err(SchemaError::MissingKey("choices"))
+            }
+            None => Game::rock_paper_scissors(),
+        };
+        let choice_index = |name: &str| {
+            game.find(name)
+                .map(|m| m.index())
+                .ok_or_else(|| SchemaError::UnknownChoice(name.to_string()))
+        };
+        let points = |entry: &str, value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| SchemaError::InvalidEntry(entry.to_string()))
+        };
+
+        let duplicate = |symbol: &str| SchemaError::DuplicateSymbol(symbol.to_string());
+        let mut opponent = HashMap::new();
+        for (symbol, name) in pairs("opponent", '=')? {
+            if opponent
+                .insert(symbol.to_string(), choice_index(name)?)
+                .is_some()
+            {
+                return Err(duplicate(symbol));
+            }
+        }
+        if opponent.is_empty() {
+            return Err(SchemaError::MissingKey("opponent"));
+        }
+
+        let mut responses = vec![];
+        for (symbol, name) in pairs("own", '=')? {
+            responses.push((symbol, Response::Choice(choice_index(name)?)));
+        }
+        for (symbol, name) in pairs("outcome", '=')? {
+            responses.push((symbol, Response::Outcome(name.parse()?)));
+        }
+        let mut response = HashMap::new();
+        for (symbol, meaning) in responses {
+            if response.insert(symbol.to_string(), meaning).is_some() {
+                return Err(duplicate(symbol));
+            }
+        }
+        if response.is_empty() {
+            return Err(SchemaError::MissingKey("own"));
+        }
+
+        let mut choice_points: Vec<usize> = game.moves().map(|m| m.points()).collect();
+        for (name, value) in pairs("choice_points", '=')? {
+            choice_points[choice_index(name)?] = points(name, value)?;
+        }
+        let mut outcome_points = [Outcome::Lose, Outcome::Draw, Outcome::Win].map(|o| o.points());
+        for (name, value) in pairs("outcome_points", '=')? {
+            outcome_points[name.parse::<Outcome>()? as usize] = points(name, value)?;
+        }
+
+        Ok(Schema {
+            game,
+            opponent,
+            response,
+            choice_points,
+            outcome_points,
+        })
+    }
+
+    pub fn choice_points(&self, choice: &Move) -> usize {
+        self.choice_points[choice.index()]
+    }
+
+    pub fn outcome_points(&self, outcome: Outcome) -> usize {
+        self.outcome_points[outcome as usize]
+    }
+
+    /// Same as `Round::total_points`, but with points from the schema
+    pub fn score(&self, round: &Round<Move>) -> usize {
+        self.choice_points(&round.own) + self.outcome_points(round.outcome())
+    }
+
+    /// Decodes a symbol of the first column
+    pub fn decode_opponent(&self, symbol: &str) -> Result<Move<'_>, SchemaError> {
+        self.opponent
+            .get(symbol)
+            .and_then(|&index| self.game.get(index))
+            .ok_or_else(|| SchemaError::UnknownSymbol(symbol.to_string()))
+    }
+
+    /// Our response to `enemy` with the highest `Schema::score`
+    pub fn best_response<'s>(&'s self, enemy: Move<'s>) -> Move<'s> {
+        self.game
+            .moves()
+            .max_by_key(|&own| self.score(&Round::new(enemy, own)))
+            .unwrap()
+    }
+
+    /// Decodes a line like `A Y`. When the response is an outcome and several choices
+    /// lead to it, the best scoring one is played.
+    pub fn decode(&self, line: &str) -> Result<Round<Move<'_>>, SchemaError> {
+        let (enemy_s, response_s) = line
+            .split_once(char::is_whitespace)
+            .map(|(a, b)| (a.trim(), b.trim()))
+            .ok_or_else(|| SchemaError::InvalidLine(line.to_string()))?;
+        let enemy = self.decode_opponent(enemy_s)?;
+        let own = match self.response.get(response_s) {
+            Some(Response::Choice(index)) => self.game.get(*index).unwrap(),
+            Some(Response::Outcome(outcome)) => self
+                .game
+                .moves()
+                .filter(|m| m.outcome(&enemy) == *outcome)
+                .max_by_key(|m| self.choice_points(m))
+                .ok_or_else(|| SchemaError::InvalidLine(line.to_string()))?,
+            None => return Err(SchemaError::UnknownSymbol(response_s.to_string())),
+        };
+        Ok(Round::new(enemy, own))
+    }
+
+    /// Total score of a strategy guide, one round per line. Blank lines are skipped.
+    pub fn total_points(&self, reader: impl Read) -> Result<usize, SchemaError> {
+        let mut total = 0;
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                total += self.score(&self.decode(&line)?);
+            }
+        }
+        Ok(total)
+    }
+}
+
+/// Our response to `enemy` with the highest `Round::total_points`
+pub fn best_response(enemy: Choice) -> Choice {
+    [enemy.looses_to(), enemy, enemy.wins()]
+        .into_iter()
+        .max_by_key(|&own| Round::new(enemy, own).total_points())
+        .unwrap()
+}
+
+/// Decodes only the first (opponent) column of a strategy guide. Blank lines are skipped.
+pub fn opponent_column(schema: &Schema, reader: impl Read) -> Result<Vec<Move<'_>>, SchemaError> {
+    let mut result = vec![];
+    for line in BufReader::new(reader).lines() {
+        if let Some(symbol) = line?.split_whitespace().next() {
+            result.push(schema.decode_opponent(symbol)?);
+        }
+    }
+    Ok(result)
+}
+
+/// Score-maximizing rounds against the given opponent choices, see `Schema::best_response`
+pub fn best_responses<'s>(schema: &'s Schema, opponent: &[Move<'s>]) -> Vec<Round<Move<'s>>> {
+    opponent
+        .iter()
+        .map(|&enemy| Round::new(enemy, schema.best_response(enemy)))
+        .collect()
+}
+
+/// `matrix[own][enemy]` is the score of a single round, see `Round::total_points`
+pub fn scoring_matrix() -> Vec<Vec<f64>> {
+    Choice::ALL
+        .iter()
+        .map(|&own| {
+            Choice::ALL
+                .iter()
+                .map(|&enemy| Round::new(enemy, own).total_points() as f64)
+                .collect()
+        })
+        .collect()
+}
+
+/// Expected score of playing `own` against `enemy`, both being probabilities of `Choice::ALL`
+pub fn expected_score(own: &[f64; 3], enemy: &[f64; 3]) -> f64 {
+    let matrix = scoring_matrix();
+    (0..3)
+        .flat_map(|i| (0..3).map(move |j| (i, j)))
+        .map(|(i, j)| own[i] * enemy[j] * matrix[i][j])
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Strategy {
+    /// Always the same choice
+    Fixed(Choice),
+    /// Best response to how often the opponent played each choice so far
+    FrequencyBased,
+    /// Probabilities of `Choice::ALL`
+    Mixed([f64; 3]),
+}
+
+impl Strategy {
+    /// Probabilities of playing each of `Choice::ALL` in the next round,
+    /// given (possibly fractional) counts of the opponent's past choices
+    pub fn distribution(&self, opponent_counts: &[f64; 3]) -> [f64; 3] {
+        match self {
+            Strategy::Fixed(choice) => {
+                let mut result = [0.0; 3];
+                result[*choice as usize] = 1.0;
+                result
+            }
+            Strategy::FrequencyBased => {
+                let total: f64 = opponent_counts.iter().sum();
+                let enemy = if total > 0.0 {
+                    opponent_counts.map(|count| count / total)
+                } else {
+                    [1.0 / 3.0; 3]
+                };
+                let matrix = scoring_matrix();
+                let score = |own: &Choice| -> f64 {
+                    let row = &matrix[*own as usize];
+                    row.iter().zip(enemy).map(|(points, p)| points * p).sum()
+                };
+                let best = Choice::ALL
+                    .into_iter()
+                    .max_by(|a, b| score(a).total_cmp(&score(b)))
+                    .unwrap();
+                Strategy::Fixed(best).distribution(opponent_counts)
+            }
+            Strategy::Mixed(probabilities) => *probabilities,
+        }
+    }
+}
+
+/// Plays `rounds` rounds between two strategies and returns their expected total scores.
+///
+/// Mixed strategies are not sampled: every round is scored by its expected value, and
+/// the opponent counts seen by `Strategy::FrequencyBased` grow by the expected choices.
+pub fn play_match(a: &Strategy, b: &Strategy, rounds: usize) -> (f64, f64) {
+    let (mut a_seen, mut b_seen) = ([0.0; 3], [0.0; 3]);
+    let (mut a_score, mut b_score) = (0.0, 0.0);
+    for _ in 0..rounds {
+        let a_plays = a.distribution(&a_seen);
+        let b_plays = b.distribution(&b_seen);
+        a_score += expected_score(&a_plays, &b_plays);
+        b_score += expected_score(&b_plays, &a_plays);
+        for i in 0..3 {
+            a_seen[i] += b_plays[i];
+            b_seen[i] += a_plays[i];
+        }
+    }
+    (a_score, b_score)
+}
+
+/// Result of a round-robin tournament
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standings {
+    /// `scores[i][j]` is the total score of strategy `i` in its match against strategy `j`
+    pub scores: Vec<Vec<f64>>,
+}
+
+impl Standings {
+    /// Sum of all match scores of every strategy
+    pub fn totals(&self) -> Vec<f64> {
+        self.scores.iter().map(|row| row.iter().sum()).collect()
+    }
+
+    /// Indices of strategies, best first
+    pub fn ranking(&self) -> Vec<usize> {
+        let totals = self.totals();
+        let mut result: Vec<usize> = (0..totals.len()).collect();
+        result.sort_by(|&a, &b| totals[b].total_cmp(&totals[a]));
+        result
+    }
+}
+
+/// Every strategy plays a match of `rounds` rounds against every other one
+pub fn round_robin(strategies: &[Strategy], rounds: usize) -> Standings {
+    let mut scores = vec![vec![0.0; strategies.len()]; strategies.len()];
+    for i in 0..strategies.len() {
+        for j in i + 1..strategies.len() {
+            let (a, b) = play_match(&strategies[i], &strategies[j], rounds);
+            scores[i][j] = a;
+            scores[j][i] = b;
+        }
+    }
+    Standings { scores }
+}
+
+/// Solves `a * x = b` by Gaussian elimination, `None` if the system is singular
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in 0..n {
+            if row != col {
+                let factor = a[row][col] / a[col][col];
+                let pivot_row = a[col].clone();
+                for (value, pivot_value) in a[row].iter_mut().zip(pivot_row).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+                b[row] -= factor * b[col];
+            }
+        }
+    }
+    Some((0..n).map(|i| b[i] / a[i][i]).collect())
+}
+
+/// Symmetric mixed-strategy equilibrium of a game where both players score by
+/// `matrix[own][enemy]`: a distribution which no pure choice scores better against.
+/// Returns the probabilities and the expected score per round.
+///
+/// Tries every support (set of choices played with a positive probability), from the
+/// largest one, and solves for the probabilities making all supported choices score the same.
+pub fn symmetric_equilibrium(matrix: &[Vec<f64>]) -> Option<(Vec<f64>, f64)> {
+    const EPSILON: f64 = 1e-9;
+    let n = matrix.len();
+    let mut supports: Vec<Vec<usize>> = (1..1usize << n)
+        .map(|mask| (0..n).filter(|i| mask & (1 << i) != 0).collect())
+        .collect();
+    supports.sort_by_key(|support: &Vec<usize>| std::cmp::Reverse(support.len()));
+
+    for support in supports {
+        // unknowns: probabilities of the support, then the value
+        let k = support.len();
+        let mut a = vec![vec![0.0; k + 1]; k + 1];
+        let mut b = vec![0.0; k + 1];
+        for (row, &i) in support.iter().enumerate() {
+            for (col, &j) in support.iter().enumerate() {
+                a[row][col] = matrix[i][j];
+            }
+            a[row][k] = -1.0;
+        }
+        a[k][..k].fill(1.0);
+        b[k] = 1.0;
+        let Some(solution) = solve_linear(a, b) else {
+            continue;
+        };
+        if solution[..k].iter().any(|&p| p < -EPSILON) {
+            continue;
+        }
+        let value = solution[k];
+        let mut probabilities = vec![0.0; n];
+        for (&i, &p) in support.iter().zip(solution.iter()) {
+            probabilities[i] = p.max(0.0);
+        }
+        let is_best_response = (0..n).all(|i| {
+            let score: f64 = (0..n).map(|j| matrix[i][j] * probabilities[j]).sum();
+            score <= value + EPSILON
+        });
+        if is_best_response {
+            return Some((probabilities, value));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_paper_scissors_choices() {
+        assert_eq!(Choice::Rock.wins(), Choice::Scissors);
+        assert_eq!(Choice::Rock.looses_to(), Choice::Paper);
+        assert_eq!(Choice::Paper.wins(), Choice::Rock);
+        assert_eq!(Choice::Scissors.looses_to(), Choice::Rock);
+        assert_eq!(Round::new(Choice::Rock, Choice::Paper).total_points(), 8);
+        assert_eq!(Round::new(Choice::Paper, Choice::Rock).total_points(), 1);
+        assert_eq!(
+            Round::new(Choice::Scissors, Choice::Scissors).total_points(),
+            6
+        );
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        assert!(game.is_balanced());
+        let spock = game.find("Spock").unwrap();
+        let lizard = game.find("Lizard").unwrap();
+        let defeated: Vec<_> = spock.defeated().map(|m| m.name()).collect();
+        assert_eq!(defeated, vec!["Rock", "Scissors"]);
+        // the enemy comes first
+        assert_eq!(Round::new(spock, lizard).total_points(), 10);
+        assert_eq!(Round::new(lizard, spock).total_points(), 5);
+        assert_eq!(Round::new(spock, spock).outcome(), Outcome::Draw);
+    }
+
+    #[test]
+    fn invalid_games() {
+        assert_eq!(Game::new(&[], &[]), Err(GameError::Empty));
+        assert_eq!(
+            Game::new(&["A", "B", "A"], &[]),
+            Err(GameError::DuplicateChoice("A".into()))
+        );
+        assert_eq!(
+            Game::new(&["A", "B"], &[("A", "C")]),
+            Err(GameError::UnknownChoice("C".into()))
+        );
+        assert_eq!(
+            Game::new(&["A", "B"], &[("A", "B"), ("B", "B")]),
+            Err(GameError::BeatsItself("B".into()))
+        );
+        assert_eq!(
+            Game::new(&["A", "B", "C"], &[("A", "B"), ("B", "C")]),
+            Err(GameError::Undecided("A".into(), "C".into()))
+        );
+        assert_eq!(
+            Game::new(&["A", "B"], &[("A", "B"), ("B", "A")]),
+            Err(GameError::Contradiction("A".into(), "B".into()))
+        );
+        // a valid tournament does not have to be balanced
+        let game = Game::new(&["A", "B", "C"], &[("A", "B"), ("B", "C"), ("A", "C")]).unwrap();
+        assert!(!game.is_balanced());
+    }
+
+    #[test]
+    fn presets() {
+        let guide = "A Y\nB X\nC Z\n";
+        assert_eq!(Schema::part1().total_points(guide.as_bytes()).unwrap(), 15);
+        assert_eq!(Schema::part2().total_points(guide.as_bytes()).unwrap(), 12);
+        assert_eq!(
+            Schema::part1().total_points("A Y\nA Q\n".as_bytes()),
+            Err(SchemaError::UnknownSymbol("Q".into()))
+        );
+        let round = Schema::part2().decode("A Y").unwrap().total_points();
+        assert_eq!(round, 4);
+    }
+
+    #[test]
+    fn custom_schema() {
+        let schema = Schema::parse(
+            "# Rock, Paper, Scissors, Lizard, Spock with flat choice points
+            choices: Rock Paper Scissors Lizard Spock
+            beats: Scissors>Paper Paper>Rock Rock>Lizard Lizard>Spock Spock>Scissors
+            beats: Scissors>Lizard Lizard>Paper Paper>Spock Spock>Rock Rock>Scissors
+            opponent: r=Rock p=Paper s=Scissors l=Lizard k=Spock
+            own: R=Rock K=Spock
+            outcome: W=Win
+            choice_points: Rock=1 Paper=2 Scissors=1 Lizard=1 Spock=1
+            outcome_points: Win=2 Draw=1",
+        )
+        .unwrap();
+        assert_eq!(
+            schema.total_points("k R\nl K\nr R\n".as_bytes()).unwrap(),
+            4
+        );
+        let round = schema.decode("k W").unwrap();
+        assert_eq!(round.own.name(), "Paper");
+        assert_eq!(schema.score(&round), 4);
+        assert_eq!(
+            schema.decode("x R").unwrap_err(),
+            SchemaError::UnknownSymbol("x".into())
+        );
+    }
+
+    #[test]
+    fn invalid_schemas() {
+        assert_eq!(
+            Schema::parse("opponent: A=Rock\nown X=Rock").unwrap_err(),
+            SchemaError::InvalidLine("own X=Rock".into())
+        );
+        assert_eq!(
+            Schema::parse("opponent: A=Rock\nmine: X=Rock").unwrap_err(),
+            SchemaError::UnknownKey("mine".into())
+        );
+        assert_eq!(
+            Schema::parse("opponent: A=Rock").unwrap_err(),
+            SchemaError::MissingKey("own")
+        );
+        assert_eq!(
+            Schema::parse("opponent: A=Stone\nown: X=Rock").unwrap_err(),
+            SchemaError::UnknownChoice("Stone".into())
+        );
+        assert_eq!(
+            Schema::parse("opponent: A=Rock\noutcome: X=Loss").unwrap_err(),
+            SchemaError::UnknownOutcome("Loss".into())
+        );
+        assert_eq!(
+            Schema::parse("choices: A B\nopponent: A=A\nown: X=A").unwrap_err(),
+            SchemaError::Game(GameError::Undecided("A".into(), "B".into()))
+        );
+    }
+
+    #[test]
+    fn duplicate_symbols() {
+        assert_eq!(
+            Schema::parse(
+                "opponent: A=Rock A=Paper
+own: X=Rock"
+            )
+            .unwrap_err(),
+            SchemaError::DuplicateSymbol("A".into())
+        );
+        assert_eq!(
+            Schema::parse(
+                "opponent: A=Rock
+own: X=Rock
+own: X=Rock"
+            )
+            .unwrap_err(),
+            SchemaError::DuplicateSymbol("X".into())
+        );
+        assert_eq!(
+            Schema::parse(
+                "opponent: A=Rock
+own: X=Rock Y=Paper
+outcome: Y=Draw"
+            )
+            .unwrap_err(),
+            SchemaError::DuplicateSymbol("Y".into())
+        );
+        // the columns are independent, so they may share symbols
+        let schema = Schema::parse(
+            "opponent: A=Rock B=Paper
+own: A=Paper B=Rock",
+        )
+        .unwrap();
+        assert_eq!(
+            schema
+                .total_points(
+                    "A A
+B B"
+                    .as_bytes()
+                )
+                .unwrap(),
+            2 + 6 + 1
+        );
+    }
+
+    #[test]
+    fn best_response_sequence() {
+        let schema = Schema::part1();
+        let opponent = opponent_column(&schema, "A Y\nB X\n\nC Z\n".as_bytes()).unwrap();
+        let names: Vec<_> = opponent.iter().map(Move::name).collect();
+        assert_eq!(names, vec!["Rock", "Paper", "Scissors"]);
+        let rounds = best_responses(&schema, &opponent);
+        let own: Vec<_> = rounds.iter().map(|round| round.own.name()).collect();
+        assert_eq!(own, vec!["Paper", "Scissors", "Rock"]);
+        let total: usize = rounds.iter().map(|round| schema.score(round)).sum();
+        assert_eq!(total, 8 + 9 + 7);
+        assert_eq!(best_response(Choice::Rock), Choice::Paper);
+
+        assert_eq!(
+            opponent_column(&schema, "D Y\n".as_bytes()).unwrap_err(),
+            SchemaError::UnknownSymbol("D".into())
+        );
+
+        // the best response follows the schema's own points
+        let schema = Schema::parse(
+            "choices: Rock Paper Scissors Lizard Spock
+            beats: Scissors>Paper Paper>Rock Rock>Lizard Lizard>Spock Spock>Scissors
+            beats: Scissors>Lizard Lizard>Paper Paper>Spock Spock>Rock Rock>Scissors
+            opponent: r=Rock s=Spock
+            own: X=Rock
+            choice_points: Paper=1 Spock=10",
+        )
+        .unwrap();
+        let opponent = opponent_column(&schema, "r\ns\n".as_bytes()).unwrap();
+        let own: Vec<_> = best_responses(&schema, &opponent)
+            .iter()
+            .map(|round| round.own.name())
+            .collect();
+        assert_eq!(own, vec!["Spock", "Spock"]);
+    }
+
+    #[test]
+    fn equilibrium() {
+        let (probabilities, value) = symmetric_equilibrium(&scoring_matrix()).unwrap();
+        for (p, expected) in probabilities.iter().zip([4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0]) {
+            assert!((p - expected).abs() < 1e-9);
+        }
+        assert!((value - 5.0).abs() < 1e-9);
+
+        // no choice does better against the equilibrium
+        let equilibrium = [probabilities[0], probabilities[1], probabilities[2]];
+        for choice in Choice::ALL {
+            let pure = Strategy::Fixed(choice).distribution(&[0.0; 3]);
+            assert!(expected_score(&pure, &equilibrium) <= value + 1e-9);
+        }
+
+        // a pure equilibrium when one choice dominates
+        let matrix = vec![vec![1.0, 1.0], vec![0.0, 0.0]];
+        assert_eq!(symmetric_equilibrium(&matrix), Some((vec![1.0, 0.0], 1.0)));
+    }
+
+    #[test]
+    fn tournament() {
+        let strategies = [
+            Strategy::Fixed(Choice::Rock),
+            Strategy::Fixed(Choice::Paper),
+            Strategy::FrequencyBased,
+            Strategy::Mixed([4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0]),
+        ];
+        let standings = round_robin(&strategies, 10);
+        // paper always beats rock
+        assert_eq!(standings.scores[1][0], 80.0);
+        assert_eq!(standings.scores[0][1], 10.0);
+        // the frequency based strategy opens with scissors, the best response to a uniform
+        // opponent, and loses against rock, then beats it
+        assert_eq!(standings.scores[2][0], 8.0 * 9.0 + 3.0);
+        // nothing scores more than 5 per round against the equilibrium
+        for i in 0..3 {
+            assert!(standings.scores[i][3] <= 50.0 + 1e-9);
+        }
+        assert_eq!(standings.ranking()[0], 2);
+    }
+}
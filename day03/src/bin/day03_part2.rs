@@ -2,55 +2,17 @@
 
 #![allow(non_upper_case_globals)]
 
-use itertools::{Chunk, Itertools};
-use std::{
-    collections::HashSet,
-    io::{BufRead, BufReader, Read},
-};
+use std::io::Read;
 
+use day03::Grouping;
 use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
 #[folder = "."]
 struct Asset;
 
-#[derive(Debug)]
-struct CommonCharError {
-    #[allow(unused)]
-    msg: String,
-}
-
-fn find_common_char<T: Iterator<Item = String>>(block: Chunk<T>) -> Result<char, CommonCharError> {
-    let intersection = block
-        .map(|line| HashSet::from_iter(line.chars()))
-        .reduce(|mut acc_set: HashSet<_>, set: HashSet<_>| {
-            acc_set.retain(|item| set.contains(item));
-            acc_set
-        })
-        .unwrap();
-
-    if intersection.len() != 1 {
-        Result::Err(CommonCharError {
-            msg: format!(
-                "Expected exactly 1 common element, but found: {:?}",
-                intersection
-            ),
-        })
-    } else {
-        Ok(intersection.into_iter().next().unwrap())
-    }
-}
-
 fn find_common_and_sum_priorities(reader: impl Read) -> Result<usize, Box<dyn std::error::Error>> {
-    let total_sum: usize = BufReader::new(reader)
-        .lines()
-        .map(|s| s.unwrap())
-        .chunks(3)
-        .into_iter()
-        .map(|block| find_common_char(block).unwrap())
-        .map(day03::get_priority)
-        .sum();
-    Ok(total_sum)
+    Ok(Grouping::PART2.priority_sum(reader)?)
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
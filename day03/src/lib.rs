@@ -1,7 +1,632 @@
-pub fn get_priority(c: char) -> usize {
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{self, BufRead, BufReader, Read},
+};
+
+pub fn try_get_priority(c: char) -> Option<usize> {
     match c {
-        'a'..='z' => (c as usize) - ('a' as usize) + 1,
-        'A'..='Z' => (c as usize) - ('A' as usize) + 27,
-        _ => panic!("Unable to determine priority for unsupported char '{}'", c),
+        'a'..='z' => Some((c as usize) - ('a' as usize) + 1),
+        'A'..='Z' => Some((c as usize) - ('A' as usize) + 27),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidItemError(pub String);
+
+impl Display for InvalidItemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unsupported item '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidItemError {}
+
+/// Set of item indices of a `PriorityScheme`. Alphabets of up to 64 items,
+/// like the puzzle's, fit into a single word and never allocate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BitSet {
+    Word(u64),
+    Words(Vec<u64>),
+}
+
+impl BitSet {
+    pub fn empty(size: usize) -> BitSet {
+        if size <= 64 {
+            BitSet::Word(0)
+        } else {
+            BitSet::Words(vec![0; size.div_ceil(64)])
+        }
+    }
+
+    pub fn full(size: usize) -> BitSet {
+        let mut set = BitSet::empty(size);
+        let words = set.words_mut();
+        words.fill(!0);
+        if !size.is_multiple_of(64) {
+            words[size / 64] = (1 << (size % 64)) - 1;
+        } else if size == 0 {
+            words[0] = 0;
+        }
+        set
+    }
+
+    fn words(&self) -> &[u64] {
+        match self {
+            BitSet::Word(word) => std::slice::from_ref(word),
+            BitSet::Words(words) => words,
+        }
+    }
+
+    fn words_mut(&mut self) -> &mut [u64] {
+        match self {
+            BitSet::Word(word) => std::slice::from_mut(word),
+            BitSet::Words(words) => words,
+        }
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        self.words_mut()[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words()
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// Both sets must come from the same scheme
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words_mut().iter_mut().zip(other.words()) {
+            *word &= other;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words()
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(|&word| word == 0)
+    }
+
+    /// Contained indices, in increasing order
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words()
+            .iter()
+            .enumerate()
+            .flat_map(|(word_idx, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    (word != 0).then(|| {
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        word_idx * 64 + bit
+                    })
+                })
+            })
+    }
+}
+
+/// Maps items to dense indices `0..size()` and indices to priorities
+pub trait PriorityScheme {
+    fn size(&self) -> usize;
+    /// `None` for unsupported items
+    fn index(&self, item: &str) -> Option<usize>;
+    fn item(&self, index: usize) -> &str;
+    fn priority(&self, index: usize) -> usize;
+
+    /// Splits the contents of a rucksack into items, every char is an item by default
+    fn split_items<'a>(&self, items: &'a str) -> Vec<&'a str> {
+        items
+            .char_indices()
+            .map(|(start, c)| &items[start..start + c.len_utf8()])
+            .collect()
+    }
+
+    fn parse_items(&self, items: &str) -> Result<BitSet, InvalidItemError> {
+        let mut set = BitSet::empty(self.size());
+        for item in self.split_items(items) {
+            let index = self
+                .index(item)
+                .ok_or_else(|| InvalidItemError(item.to_string()))?;
+            set.insert(index);
+        }
+        Ok(set)
+    }
+
+    /// Items of the set, ordered by index
+    fn items_of(&self, set: &BitSet) -> Vec<&str> {
+        set.indices().map(|index| self.item(index)).collect()
+    }
+
+    fn priority_sum_of(&self, set: &BitSet) -> usize {
+        set.indices().map(|index| self.priority(index)).sum()
+    }
+}
+
+const AOC_ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// The puzzle's scheme: `a..=z` have priorities 1 through 26 and `A..=Z` 27 through 52,
+/// see `try_get_priority`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AocPriorities;
+
+impl PriorityScheme for AocPriorities {
+    fn size(&self) -> usize {
+        AOC_ITEMS.len()
+    }
+
+    fn index(&self, item: &str) -> Option<usize> {
+        let mut chars = item.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => try_get_priority(c).map(|priority| priority - 1),
+            _ => None,
+        }
+    }
+
+    fn item(&self, index: usize) -> &str {
+        &AOC_ITEMS[index..=index]
+    }
+
+    fn priority(&self, index: usize) -> usize {
+        index + 1
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SchemeError {
+    DuplicateItem(String),
+    /// 1-based line number and the line itself
+    InvalidLine(usize, String),
+}
+
+impl Display for SchemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemeError::DuplicateItem(item) => write!(f, "Duplicate item '{item}'"),
+            SchemeError::InvalidLine(line_number, line) => {
+                write!(f, "Invalid line {line_number}: '{line}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemeError {}
+
+/// Arbitrary Unicode items, prioritized by their 1-based position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    items: Vec<String>,
+    indices: HashMap<String, usize>,
+    /// Whether some item is longer than a single char, like a SKU code
+    multi_char: bool,
+}
+
+impl Alphabet {
+    /// Every char of `items` is an item
+    pub fn new(items: &str) -> Result<Alphabet, SchemeError> {
+        Alphabet::from_items(AocPriorities.split_items(items))
+    }
+
+    fn from_items<'a>(items: impl IntoIterator<Item = &'a str>) -> Result<Alphabet, SchemeError> {
+        let mut alphabet = Alphabet {
+            items: vec![],
+            indices: HashMap::new(),
+            multi_char: false,
+        };
+        for item in items {
+            if alphabet
+                .indices
+                .insert(item.to_string(), alphabet.items.len())
+                .is_some()
+            {
+                return Err(SchemeError::DuplicateItem(item.to_string()));
+            }
+            alphabet.multi_char |= item.chars().nth(1).is_some();
+            alphabet.items.push(item.to_string());
+        }
+        Ok(alphabet)
+    }
+}
+
+impl PriorityScheme for Alphabet {
+    fn size(&self) -> usize {
+        self.items.len()
+    }
+
+    fn index(&self, item: &str) -> Option<usize> {
+        self.indices.get(item).copied()
+    }
+
+    fn item(&self, index: usize) -> &str {
+        &self.items[index]
+    }
+
+    fn priority(&self, index: usize) -> usize {
+        index + 1
+    }
+
+    /// Items longer than a single char have to be separated by whitespace
+    fn split_items<'a>(&self, items: &'a str) -> Vec<&'a str> {
+        if self.multi_char {
+            items.split_whitespace().collect()
+        } else {
+            AocPriorities.split_items(items)
+        }
+    }
+}
+
+/// Items with explicit priorities
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityTable {
+    alphabet: Alphabet,
+    priorities: Vec<usize>,
+}
+
+impl PriorityTable {
+    /// Parses an item and its priority per line, e.g. `€ 100` or `SKU-1042 7`.
+    /// Blank lines and lines starting with `#` are ignored. If some item is longer
+    /// than a single char, items in rucksacks have to be separated by whitespace.
+    pub fn parse(table: &str) -> Result<PriorityTable, SchemeError> {
+        let mut items = vec![];
+        let mut priorities = vec![];
+        for (line_idx, line) in table.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || SchemeError::InvalidLine(line_idx + 1, line.to_string());
+            let (item, priority) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            items.push(item);
+            priorities.push(priority.trim().parse().map_err(|_| invalid())?);
+        }
+        Ok(PriorityTable {
+            alphabet: Alphabet::from_items(items)?,
+            priorities,
+        })
+    }
+}
+
+impl PriorityScheme for PriorityTable {
+    fn size(&self) -> usize {
+        self.alphabet.size()
+    }
+
+    fn index(&self, item: &str) -> Option<usize> {
+        self.alphabet.index(item)
+    }
+
+    fn item(&self, index: usize) -> &str {
+        self.alphabet.item(index)
+    }
+
+    fn priority(&self, index: usize) -> usize {
+        self.priorities[index]
+    }
+
+    fn split_items<'a>(&self, items: &'a str) -> Vec<&'a str> {
+        self.alphabet.split_items(items)
+    }
+}
+
+/// Items present in every one of `groups`, or no items if there are no groups at all
+pub fn common_items<'a, S: PriorityScheme>(
+    scheme: &S,
+    groups: impl IntoIterator<Item = &'a str>,
+) -> Result<BitSet, InvalidItemError> {
+    let mut result: Option<BitSet> = None;
+    for group in groups {
+        let set = scheme.parse_items(group)?;
+        match result.as_mut() {
+            Some(acc) => acc.intersect_with(&set),
+            None => result = Some(set),
+        }
+    }
+    Ok(result.unwrap_or_else(|| BitSet::empty(scheme.size())))
+}
+
+#[derive(Debug)]
+pub enum RucksackError {
+    Io(io::Error),
+    /// Compartment count and group size must be positive
+    InvalidGrouping,
+    /// 1-based line number, its length in items and the compartment count
+    UnevenCompartments(usize, usize, usize),
+    /// 1-based line number of the first rucksack of the group and the group size
+    IncompleteGroup(usize, usize),
+    /// 1-based line number and an item outside of the alphabet
+    InvalidItem(usize, String),
+}
+
+impl Display for RucksackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RucksackError::Io(err) => write!(f, "Error reading input: {err}"),
+            RucksackError::InvalidGrouping => {
+                write!(f, "Compartment count and group size must be positive")
+            }
+            RucksackError::UnevenCompartments(line, length, compartments) => write!(
+                f,
+                "Line {line}: {length} items cannot be split into {compartments} compartments"
+            ),
+            RucksackError::IncompleteGroup(line, size) => {
+                write!(f, "Line {line}: the last group has only {size} rucksacks")
+            }
+            RucksackError::InvalidItem(line, item) => {
+                write!(f, "Line {line}: unsupported item '{item}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RucksackError {}
+
+impl From<io::Error> for RucksackError {
+    fn from(value: io::Error) -> Self {
+        RucksackError::Io(value)
+    }
+}
+
+/// Items shared by all compartments of all rucksacks in a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupItems {
+    /// 1-based line number of the first rucksack of the group
+    pub first_line: usize,
+    /// Indices of the items in the alphabet of the grouping
+    pub items: BitSet,
+}
+
+/// Describes how rucksacks are split into compartments and grouped together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grouping<S = AocPriorities> {
+    pub compartments: usize,
+    pub group_size: usize,
+    /// Items allowed in rucksacks and their priorities
+    pub alphabet: S,
+}
+
+impl Grouping {
+    /// Items shared by the two halves of every rucksack
+    pub const PART1: Grouping = Grouping::new(2, 1);
+    /// Items shared by every three rucksacks
+    pub const PART2: Grouping = Grouping::new(1, 3);
+
+    pub const fn new(compartments: usize, group_size: usize) -> Grouping {
+        Grouping {
+            compartments,
+            group_size,
+            alphabet: AocPriorities,
+        }
+    }
+}
+
+impl<S: PriorityScheme> Grouping<S> {
+    pub fn with_alphabet<T: PriorityScheme>(self, alphabet: T) -> Grouping<T> {
+        Grouping {
+            compartments: self.compartments,
+            group_size: self.group_size,
+            alphabet,
+        }
+    }
+
+    /// Items shared by all compartments of a single rucksack
+    pub fn rucksack_items(&self, line_number: usize, line: &str) -> Result<BitSet, RucksackError> {
+        let items = self.alphabet.split_items(line);
+        if !items.len().is_multiple_of(self.compartments) {
+            return Err(RucksackError::UnevenCompartments(
+                line_number,
+                items.len(),
+                self.compartments,
+            ));
+        }
+        let size = self.alphabet.size();
+        if items.is_empty() {
+            return Ok(BitSet::empty(size));
+        }
+        let mut result = BitSet::full(size);
+        for compartment in items.chunks(items.len() / self.compartments) {
+            let mut set = BitSet::empty(size);
+            for &item in compartment {
+                let index = self
+                    .alphabet
+                    .index(item)
+                    .ok_or_else(|| RucksackError::InvalidItem(line_number, item.to_string()))?;
+                set.insert(index);
+            }
+            result.intersect_with(&set);
+        }
+        Ok(result)
+    }
+
+    /// Common items of every group, one rucksack per line. Blank lines are skipped.
+    pub fn common_items(&self, reader: impl Read) -> Result<Vec<GroupItems>, RucksackError> {
+        if self.compartments == 0 || self.group_size == 0 {
+            return Err(RucksackError::InvalidGrouping);
+        }
+        let mut result = vec![];
+        let mut current: Option<GroupItems> = None;
+        let mut size = 0;
+        for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let items = self.rucksack_items(line_idx + 1, line.trim())?;
+            match current.as_mut() {
+                Some(group) => group.items.intersect_with(&items),
+                None => {
+                    current = Some(GroupItems {
+                        first_line: line_idx + 1,
+                        items,
+                    })
+                }
+            }
+            size += 1;
+            if size == self.group_size {
+                result.extend(current.take());
+                size = 0;
+            }
+        }
+        if let Some(group) = current {
+            return Err(RucksackError::IncompleteGroup(group.first_line, size));
+        }
+        Ok(result)
+    }
+
+    /// Sum of priorities of all common items of all groups
+    pub fn priority_sum(&self, reader: impl Read) -> Result<usize, RucksackError> {
+        Ok(self
+            .common_items(reader)?
+            .iter()
+            .map(|group| self.alphabet.priority_sum_of(&group.items))
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_sets() {
+        assert_eq!(BitSet::full(52).len(), 52);
+        assert_eq!(BitSet::full(52).indices().last(), Some(51));
+        assert_eq!(BitSet::full(64).len(), 64);
+        assert_eq!(BitSet::full(130).len(), 130);
+        assert!(BitSet::full(0).is_empty());
+        assert!(matches!(BitSet::empty(64), BitSet::Word(0)));
+        let mut set = BitSet::empty(200);
+        set.insert(3);
+        set.insert(199);
+        assert_eq!(set.indices().collect::<Vec<_>>(), vec![3, 199]);
+        set.intersect_with(&BitSet::full(200));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn common() {
+        let common = common_items(&AocPriorities, ["vJrwpWtwJgWr", "hcsFMMfFFhFp"]).unwrap();
+        assert_eq!(AocPriorities.items_of(&common), vec!["p"]);
+        let common = common_items(&AocPriorities, ["abcXY", "bcdY", "cbY"]).unwrap();
+        assert_eq!(AocPriorities.items_of(&common), vec!["b", "c", "Y"]);
+        assert_eq!(AocPriorities.priority_sum_of(&common), 2 + 3 + 51);
+        assert!(common_items(&AocPriorities, ["abc", "xyz"])
+            .unwrap()
+            .is_empty());
+        assert!(common_items(&AocPriorities, []).unwrap().is_empty());
+        assert_eq!(
+            common_items(&AocPriorities, ["a1"]),
+            Err(InvalidItemError("1".into()))
+        );
+        assert_eq!(AocPriorities.index("ab"), None);
+        assert_eq!(AocPriorities.item(51), "Z");
+    }
+
+    #[test]
+    fn groupings() {
+        let input =
+            "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\n";
+        let groups = Grouping::PART1.common_items(input.as_bytes()).unwrap();
+        let alphabet = Grouping::PART1.alphabet;
+        let items: Vec<_> = groups.iter().map(|g| alphabet.items_of(&g.items)).collect();
+        assert_eq!(items, vec![["p"], ["L"], ["P"]]);
+        assert_eq!(groups[2].first_line, 3);
+
+        let groups = Grouping::PART2.common_items(input.as_bytes()).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(AocPriorities.items_of(&groups[0].items), vec!["r"]);
+
+        let groups = Grouping::new(3, 2).common_items("abacad\ncadaba\n".as_bytes());
+        assert_eq!(AocPriorities.items_of(&groups.unwrap()[0].items), vec!["a"]);
+    }
+
+    #[test]
+    fn grouping_errors() {
+        let lowercase = Alphabet::new("abcdefghijklmnopqrstuvwxyz").unwrap();
+        let err = Grouping::PART1.common_items("abab\nabc\n".as_bytes());
+        assert!(matches!(
+            err,
+            Err(RucksackError::UnevenCompartments(2, 3, 2))
+        ));
+        let err = Grouping::PART2.common_items("ab\nba\nab\nab\n".as_bytes());
+        assert!(matches!(err, Err(RucksackError::IncompleteGroup(4, 1))));
+        let grouping = Grouping::PART1.with_alphabet(lowercase);
+        let err = grouping.common_items("abab\nabAb\n".as_bytes());
+        assert!(matches!(err, Err(RucksackError::InvalidItem(2, item)) if item == "A"));
+        let err = Grouping::new(0, 1).common_items("ab".as_bytes());
+        assert!(matches!(err, Err(RucksackError::InvalidGrouping)));
+    }
+
+    #[test]
+    fn schemes() {
+        let alphabet = Alphabet::new("αβγδ😀").unwrap();
+        let common = common_items(&alphabet, ["αβ😀", "😀γβ"]).unwrap();
+        assert_eq!(alphabet.items_of(&common), vec!["β", "😀"]);
+        assert_eq!(alphabet.priority_sum_of(&common), 2 + 5);
+        assert_eq!(
+            common_items(&alphabet, ["αx"]),
+            Err(InvalidItemError("x".into()))
+        );
+        assert_eq!(
+            Alphabet::new("abca"),
+            Err(SchemeError::DuplicateItem("a".into()))
+        );
+
+        let table = PriorityTable::parse("# SKU weights\nA 100\nB 20\n\nZ 3").unwrap();
+        let grouping = Grouping::new(1, 2).with_alphabet(table);
+        assert_eq!(
+            grouping.priority_sum("AZB\nZA\nBB\nAB".as_bytes()).unwrap(),
+            103 + 20
+        );
+        assert_eq!(
+            PriorityTable::parse("A 1\nB"),
+            Err(SchemeError::InvalidLine(2, "B".into()))
+        );
+        assert_eq!(
+            PriorityTable::parse("A x"),
+            Err(SchemeError::InvalidLine(1, "A x".into()))
+        );
+
+        // more than 64 items
+        let many: String = ('\u{4e00}'..'\u{4e80}').collect();
+        let alphabet = Alphabet::new(&many).unwrap();
+        let common = common_items(&alphabet, [&many[..], "\u{4e7f}\u{4e00}"]).unwrap();
+        assert_eq!(alphabet.priority_sum_of(&common), 128 + 1);
+        assert_eq!(common.len(), 2);
+        assert!(common.contains(127) && !common.contains(1) && !common.contains(128));
+    }
+
+    #[test]
+    fn sku_codes() {
+        let table = PriorityTable::parse("SKU-1042 7\nSKU-7 30\nBOLT 1").unwrap();
+        let common = common_items(&table, ["SKU-7 BOLT SKU-1042", "BOLT  SKU-7"]).unwrap();
+        assert_eq!(table.items_of(&common), vec!["SKU-7", "BOLT"]);
+        assert_eq!(table.priority_sum_of(&common), 31);
+        assert_eq!(
+            common_items(&table, ["SKU-7 SKU-8"]),
+            Err(InvalidItemError("SKU-8".into()))
+        );
+
+        let grouping = Grouping::new(2, 1).with_alphabet(table);
+        let input = "SKU-7 BOLT BOLT SKU-1042\nSKU-7 SKU-1042 BOLT\n";
+        assert!(matches!(
+            grouping.common_items(input.as_bytes()),
+            Err(RucksackError::UnevenCompartments(2, 3, 2))
+        ));
+        assert_eq!(
+            grouping
+                .priority_sum(input.lines().next().unwrap().as_bytes())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            PriorityTable::parse("AB 1\nAB 2"),
+            Err(SchemeError::DuplicateItem("AB".into()))
+        );
     }
 }
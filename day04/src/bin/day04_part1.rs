@@ -2,32 +2,21 @@
 
 #![allow(non_upper_case_globals)]
 
-use std::io::{BufRead, BufReader, Read};
+use std::io::Read;
 
-use day04::Range;
+use day04::Assignments;
 use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
 #[folder = "."]
 struct Asset;
 
+/// Counts groups of elves where a single range contains all the others
 fn count_fully_contained_ranges(reader: impl Read) -> Result<usize, Box<dyn std::error::Error>> {
-    let fully_contained_count = BufReader::new(reader)
-        .lines()
-        .map(|line_maybe| line_maybe.unwrap())
-        .map(|line| {
-            let mut parts: Vec<Range> = line.split(',').map(|s| s.into()).collect();
-            if parts.len() != 2 {
-                panic!("Expected to have exactly 2 ranges, but found: {:?}", parts);
-            }
-            let second = parts.pop().unwrap();
-            let first = parts.pop().unwrap();
-
-            (first, second)
-        })
-        .filter(|(range_one, range_two)| {
-            range_one.contains_fully(range_two) || range_two.contains_fully(range_one)
-        })
+    let fully_contained_count = Assignments::parse(reader)?
+        .group_reports()
+        .iter()
+        .filter(|report| report.container.is_some())
         .count();
     Ok(fully_contained_count)
 }
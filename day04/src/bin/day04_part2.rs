@@ -2,30 +2,21 @@
 
 #![allow(non_upper_case_globals)]
 
-use std::io::{BufRead, BufReader, Read};
+use std::io::Read;
 
+use day04::Assignments;
 use rust_embed::RustEmbed;
 
 #[derive(RustEmbed)]
 #[folder = "."]
 struct Asset;
 
+/// Counts groups of elves where all ranges overlap
 fn count_overlaps(reader: impl Read) -> Result<usize, Box<dyn std::error::Error>> {
-    let overlaped_count = BufReader::new(reader)
-        .lines()
-        .map(|line| {
-            let mut parts: Vec<day04::Range> = line.unwrap().split(',').map(|s| s.into()).collect();
-            if parts.len() != 2 {
-                panic!("Expected to have exactly 2 ranges, but found: {:?}", parts);
-            }
-            let second = parts.pop().unwrap();
-            let first = parts.pop().unwrap();
-
-            (first, second)
-        })
-        .filter(|(range_one, range_two)| {
-            range_one.overlaps(range_two) || range_two.overlaps(range_one)
-        })
+    let overlaped_count = Assignments::parse(reader)?
+        .group_reports()
+        .iter()
+        .filter(|report| report.intersection.is_some())
         .count();
     Ok(overlaped_count)
 }
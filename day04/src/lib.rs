@@ -1,31 +1,714 @@
-#[derive(Debug)]
-pub struct Range {
-    start: u32,
-    end: u32,
+use std::{
+    fmt::{Debug, Display},
+    io::{self, BufRead, BufReader, Read},
+    str::FromStr,
+};
+
+/// Integer-like bounds of an `Interval`
+pub trait Discrete: Copy + Ord + Debug {
+    fn checked_next(self) -> Option<Self>;
+    fn checked_prev(self) -> Option<Self>;
+    /// Number of values in `start..=end`, saturating at `u64::MAX`
+    fn count(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                fn checked_next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_prev(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(start: Self, end: Self) -> u64 {
+                    u64::try_from(end.abs_diff(start))
+                        .unwrap_or(u64::MAX)
+                        .saturating_add(1)
+                }
+            }
+        )*
+    };
 }
 
-impl From<&str> for Range {
+impl_discrete!(u32, u64, usize, i32, i64);
+
+/// Inclusive interval `start..=end`, never empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+/// Section assignment of a single elf
+pub type Range = Interval<u32>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseRangeError(String);
+
+impl Display for ParseRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected ranges to be in format `number-number`, but found: {}",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+impl<T: Discrete + FromStr> FromStr for Interval<T> {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRangeError(s.to_string());
+        let s = s.trim();
+        // the first `-` may be a sign of the start
+        let split = s.char_indices().skip(1).find(|&(_, c)| c == '-');
+        let (start, end) = split
+            .map(|(i, _)| (&s[..i], &s[i + 1..]))
+            .ok_or_else(error)?;
+        let (start, end) = (
+            start.parse().map_err(|_| error())?,
+            end.parse().map_err(|_| error())?,
+        );
+        Interval::try_new(start, end).ok_or_else(error)
+    }
+}
+
+impl<T: Discrete + FromStr> From<&str> for Interval<T> {
     fn from(s: &str) -> Self {
-        let splits: Vec<_> = s.split('-').map(|s| s.parse::<u32>().unwrap()).collect();
-        if splits.len() != 2 {
-            panic!(
-                "Expected ranges to be in format `number-number`, but found: {}",
-                s
-            )
-        }
-        Range {
-            start: *splits.first().unwrap(),
-            end: *splits.get(1).unwrap(),
-        }
+        s.parse().unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
-impl Range {
-    pub fn contains_fully(&self, other: &Range) -> bool {
+impl<T: Discrete> From<Interval<T>> for std::ops::RangeInclusive<T> {
+    fn from(value: Interval<T>) -> Self {
+        value.start..=value.end
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl<T: Discrete> Interval<T> {
+    /// Panics if `start > end`
+    pub fn new(start: T, end: T) -> Self {
+        Interval::try_new(start, end)
+            .unwrap_or_else(|| panic!("Invalid interval {start:?}..={end:?}"))
+    }
+
+    pub fn try_new(start: T, end: T) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    /// Interval of a single value
+    pub fn point(value: T) -> Self {
+        Interval {
+            start: value,
+            end: value,
+        }
+    }
+
+    /// Number of values in the interval
+    pub fn length(&self) -> u64 {
+        T::count(self.start, self.end)
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.start <= *value && *value <= self.end
+    }
+
+    pub fn contains_fully(&self, other: &Interval<T>) -> bool {
         self.start <= other.start && self.end >= other.end
     }
 
-    pub fn overlaps(&self, other: &Range) -> bool {
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
         !(self.end < other.start || self.start > other.end)
     }
+
+    /// Whether the intervals do not overlap, but there is no gap between them either
+    pub fn is_adjacent(&self, other: &Interval<T>) -> bool {
+        self.end.checked_next() == Some(other.start) || other.end.checked_next() == Some(self.start)
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::try_new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// `None` if the union is not a single interval
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        (self.overlaps(other) || self.is_adjacent(other)).then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    /// Values of `self` not in `other`: up to two intervals, ordered
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let before = other
+            .start
+            .checked_prev()
+            .and_then(|end| Interval::try_new(self.start, end));
+        let after = other
+            .end
+            .checked_next()
+            .and_then(|start| Interval::try_new(start, self.end));
+        before.into_iter().chain(after).collect()
+    }
+}
+
+/// Sorted disjoint intervals, where overlapping and adjacent intervals are merged
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Number of disjoint intervals
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let touches = |i: &Interval<T>| i.overlaps(&interval) || i.is_adjacent(&interval);
+        let first = self
+            .intervals
+            .partition_point(|i| i.end < interval.start && !touches(i));
+        let last = self
+            .intervals
+            .partition_point(|i| i.start <= interval.end || touches(i));
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |acc, i| acc.union(i).unwrap());
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: &Interval<T>) {
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let remaining: Vec<_> = self.intervals[first..last]
+            .iter()
+            .flat_map(|i| i.difference(interval))
+            .collect();
+        self.intervals.splice(first..last, remaining);
+    }
+
+    fn find(&self, value: &T) -> Option<&Interval<T>> {
+        let idx = self.intervals.partition_point(|i| i.end < *value);
+        self.intervals.get(idx).filter(|i| i.contains(value))
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.find(value).is_some()
+    }
+
+    /// Whether every value of `interval` is in the set
+    pub fn covers(&self, interval: &Interval<T>) -> bool {
+        self.find(&interval.start)
+            .is_some_and(|i| i.contains_fully(interval))
+    }
+
+    /// Total number of values in the set
+    pub fn covered_length(&self) -> u64 {
+        self.intervals.iter().map(Interval::length).sum()
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.insert(*interval);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = vec![];
+        let (mut a, mut b) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (&self.intervals[a], &other.intervals[b]);
+            intervals.extend(x.intersection(y));
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for interval in other.iter() {
+            result.remove(interval);
+        }
+        result
+    }
+
+    /// Values of `within` not in the set
+    pub fn gaps(&self, within: &Interval<T>) -> IntervalSet<T> {
+        IntervalSet::from_iter([*within]).difference(self)
+    }
+}
+
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// Static interval tree: entries sorted by start form an implicit balanced binary search tree,
+/// where every node also knows the maximal end within its subtree
+#[derive(Debug, Clone)]
+pub struct IntervalTree<T, V> {
+    entries: Vec<(Interval<T>, V)>,
+    max_end: Vec<T>,
+}
+
+impl<T: Discrete, V> IntervalTree<T, V> {
+    pub fn new(mut entries: Vec<(Interval<T>, V)>) -> Self {
+        entries.sort_by_key(|(interval, _)| *interval);
+        let mut max_end: Vec<T> = entries.iter().map(|(interval, _)| interval.end).collect();
+        Self::build(&entries, &mut max_end, 0, entries.len());
+        IntervalTree { entries, max_end }
+    }
+
+    fn build(entries: &[(Interval<T>, V)], max_end: &mut [T], lo: usize, hi: usize) -> Option<T> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let left = Self::build(entries, max_end, lo, mid);
+        let right = Self::build(entries, max_end, mid + 1, hi);
+        let max = [left, right]
+            .into_iter()
+            .flatten()
+            .fold(entries[mid].0.end, T::max);
+        max_end[mid] = max;
+        Some(max)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries overlapping `interval`, ordered by their intervals
+    pub fn overlapping(&self, interval: &Interval<T>) -> Vec<&(Interval<T>, V)> {
+        let mut result = vec![];
+        self.collect(interval, 0, self.entries.len(), &mut result);
+        result
+    }
+
+    /// Entries containing `value`, ordered by their intervals
+    pub fn containing(&self, value: T) -> Vec<&(Interval<T>, V)> {
+        self.overlapping(&Interval::point(value))
+    }
+
+    fn collect<'a>(
+        &'a self,
+        query: &Interval<T>,
+        lo: usize,
+        hi: usize,
+        result: &mut Vec<&'a (Interval<T>, V)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.max_end[mid] < query.start {
+            return;
+        }
+        self.collect(query, lo, mid, result);
+        // everything to the right starts even later
+        if self.entries[mid].0.start > query.end {
+            return;
+        }
+        if self.entries[mid].0.overlaps(query) {
+            result.push(&self.entries[mid]);
+        }
+        self.collect(query, mid + 1, hi, result);
+    }
+}
+
+/// Containment analysis of a group of elves, members are referred to by their position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupReport {
+    /// First member whose range contains the ranges of all the others
+    pub container: Option<usize>,
+    /// Sections assigned to every member
+    pub intersection: Option<Range>,
+    /// Members whose sections are all covered by the rest of the group. Each of them
+    /// could be removed on its own, but not necessarily all of them at once.
+    pub redundant: Vec<usize>,
+}
+
+impl GroupReport {
+    pub fn new(ranges: &[Range]) -> GroupReport {
+        let container = ranges
+            .iter()
+            .position(|range| ranges.iter().all(|other| range.contains_fully(other)));
+        let intersection = ranges.split_first().and_then(|(first, rest)| {
+            rest.iter()
+                .try_fold(*first, |acc, range| acc.intersection(range))
+        });
+        let redundant = (0..ranges.len())
+            .filter(|&member| {
+                let rest: IntervalSet<u32> = ranges
+                    .iter()
+                    .enumerate()
+                    .filter(|&(other, _)| other != member)
+                    .map(|(_, range)| *range)
+                    .collect();
+                rest.covers(&ranges[member])
+            })
+            .collect();
+        GroupReport {
+            container,
+            intersection,
+            redundant,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AssignmentError {
+    Io(io::Error),
+    /// 1-based line number and the cause
+    InvalidRange(usize, ParseRangeError),
+}
+
+impl Display for AssignmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssignmentError::Io(err) => write!(f, "Error reading input: {err}"),
+            AssignmentError::InvalidRange(line, err) => write!(f, "Line {line}: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AssignmentError {}
+
+impl From<io::Error> for AssignmentError {
+    fn from(value: io::Error) -> Self {
+        AssignmentError::Io(value)
+    }
+}
+
+/// Identifies an elf by its line in the input and its position within that line, both 0-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElfId {
+    pub group: usize,
+    pub member: usize,
+}
+
+/// Section assignments of all elves
+#[derive(Debug, Clone)]
+pub struct Assignments {
+    pub groups: Vec<Vec<Range>>,
+    tree: IntervalTree<u32, ElfId>,
+}
+
+impl Assignments {
+    /// Parses comma separated ranges, one group of elves per line, e.g. `2-4,6-8`.
+    /// Blank lines are skipped.
+    pub fn parse(reader: impl Read) -> Result<Assignments, AssignmentError> {
+        let mut groups = vec![];
+        for (line_idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let group = line
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<Range>, _>>()
+                .map_err(|err| AssignmentError::InvalidRange(line_idx + 1, err))?;
+            groups.push(group);
+        }
+        Ok(Assignments::new(groups))
+    }
+
+    pub fn new(groups: Vec<Vec<Range>>) -> Assignments {
+        let entries = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(group, ranges)| {
+                ranges
+                    .iter()
+                    .enumerate()
+                    .map(move |(member, range)| (*range, ElfId { group, member }))
+            })
+            .collect();
+        Assignments {
+            tree: IntervalTree::new(entries),
+            groups,
+        }
+    }
+
+    pub fn range(&self, elf: ElfId) -> Range {
+        self.groups[elf.group][elf.member]
+    }
+
+    /// Elves assigned to the section, in no particular order
+    pub fn elves_at(&self, section: u32) -> Vec<ElfId> {
+        self.tree
+            .containing(section)
+            .into_iter()
+            .map(|(_, elf)| *elf)
+            .collect()
+    }
+
+    /// Elves with at least one section in `range`, in no particular order
+    pub fn elves_overlapping(&self, range: &Range) -> Vec<ElfId> {
+        self.tree
+            .overlapping(range)
+            .into_iter()
+            .map(|(_, elf)| *elf)
+            .collect()
+    }
+
+    /// Sweeps over range starts and ends. Returns the maximum number of elves assigned
+    /// to the same section and all sections where that happens.
+    pub fn max_concurrency(&self) -> (usize, IntervalSet<u32>) {
+        // (section, change): ends come right after the section, so they sort after starts
+        let mut events: Vec<(u64, isize)> = self
+            .groups
+            .iter()
+            .flatten()
+            .flat_map(|range| [(range.start as u64, 1), (range.end as u64 + 1, -1)])
+            .collect();
+        events.sort_by_key(|&(section, change)| (section, -change));
+
+        let mut max = 0;
+        let mut sections = IntervalSet::new();
+        let mut current = 0;
+        for (idx, &(section, change)) in events.iter().enumerate() {
+            current += change;
+            let Some(&(next, _)) = events.get(idx + 1) else {
+                break;
+            };
+            if next == section || current == 0 {
+                continue;
+            }
+            let covered = Interval::new(section as u32, (next - 1) as u32);
+            if current as usize > max {
+                max = current as usize;
+                sections = IntervalSet::new();
+            }
+            if current as usize == max {
+                sections.insert(covered);
+            }
+        }
+        (max, sections)
+    }
+
+    pub fn group_reports(&self) -> Vec<GroupReport> {
+        self.groups
+            .iter()
+            .map(|group| GroupReport::new(group))
+            .collect()
+    }
+
+    /// Sections within `within` nobody is assigned to
+    pub fn uncovered(&self, within: &Range) -> IntervalSet<u32> {
+        let covered: IntervalSet<u32> = self.groups.iter().flatten().copied().collect();
+        covered.gaps(within)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|&(s, e)| Interval::new(s, e))
+            .collect()
+    }
+
+    fn bounds(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter().map(|i| (i.start, i.end)).collect()
+    }
+
+    #[test]
+    fn interval() {
+        let range: Range = "2-8".parse().unwrap();
+        assert_eq!(range, Interval::new(2, 8));
+        assert_eq!(range.length(), 7);
+        assert_eq!(range.to_string(), "2-8");
+        assert!("8-2".parse::<Range>().is_err());
+        assert!("2-x".parse::<Range>().is_err());
+        assert!("-2-3".parse::<Range>().is_err());
+        assert_eq!("-5--2".parse::<Interval<i32>>(), Ok(Interval::new(-5, -2)));
+
+        let a = Interval::new(3, 7);
+        assert_eq!(
+            a.intersection(&Interval::new(5, 9)),
+            Some(Interval::new(5, 7))
+        );
+        assert_eq!(a.intersection(&Interval::new(8, 9)), None);
+        assert_eq!(a.union(&Interval::new(8, 9)), Some(Interval::new(3, 9)));
+        assert_eq!(a.union(&Interval::new(9, 9)), None);
+        assert_eq!(
+            a.difference(&Interval::new(4, 5)),
+            vec![Interval::new(3, 3), Interval::new(6, 7)]
+        );
+        assert_eq!(a.difference(&Interval::new(0, 9)), vec![]);
+        assert_eq!(a.difference(&Interval::new(8, 9)), vec![a]);
+        assert_eq!(
+            Interval::new(0u32, 0).difference(&Interval::point(0)),
+            vec![]
+        );
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).length(), u64::MAX);
+    }
+
+    #[test]
+    fn interval_set() {
+        let mut s = set(&[(10, 12), (1, 3), (5, 6)]);
+        assert_eq!(bounds(&s), vec![(1, 3), (5, 6), (10, 12)]);
+        s.insert(Interval::new(4, 4));
+        assert_eq!(bounds(&s), vec![(1, 6), (10, 12)]);
+        s.insert(Interval::new(7, 9));
+        assert_eq!(bounds(&s), vec![(1, 12)]);
+        s.remove(&Interval::new(3, 4));
+        s.remove(&Interval::new(12, 20));
+        assert_eq!(bounds(&s), vec![(1, 2), (5, 11)]);
+        assert_eq!(s.covered_length(), 9);
+        assert!(s.contains(&5) && !s.contains(&3) && !s.contains(&12));
+        assert!(s.covers(&Interval::new(6, 11)) && !s.covers(&Interval::new(2, 5)));
+        assert_eq!(
+            bounds(&s.gaps(&Interval::new(0, 13))),
+            vec![(0, 0), (3, 4), (12, 13)]
+        );
+
+        let other = set(&[(0, 1), (4, 6), (9, 20)]);
+        assert_eq!(bounds(&s.union(&other)), vec![(0, 2), (4, 20)]);
+        assert_eq!(
+            bounds(&s.intersection(&other)),
+            vec![(1, 1), (5, 6), (9, 11)]
+        );
+        assert_eq!(bounds(&s.difference(&other)), vec![(2, 2), (7, 8)]);
+    }
+
+    #[test]
+    fn interval_tree() {
+        let entries: Vec<_> = (0..100)
+            .map(|i| (Interval::new(i * 7 % 50, i * 7 % 50 + i % 13), i))
+            .collect();
+        let tree = IntervalTree::new(entries.clone());
+        for query in [
+            Interval::new(0, 0),
+            Interval::new(10, 20),
+            Interval::new(55, 70),
+        ] {
+            let mut found: Vec<_> = tree.overlapping(&query).iter().map(|(_, v)| *v).collect();
+            let mut expected: Vec<_> = entries
+                .iter()
+                .filter(|(interval, _)| interval.overlaps(&query))
+                .map(|(_, v)| *v)
+                .collect();
+            found.sort();
+            expected.sort();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn assignments() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n";
+        let assignments = Assignments::parse(input.as_bytes()).unwrap();
+        let mut at_nine = assignments.elves_at(9);
+        at_nine.sort();
+        assert_eq!(
+            at_nine,
+            vec![ElfId {
+                group: 2,
+                member: 1
+            }]
+        );
+        assert_eq!(assignments.range(at_nine[0]), Interval::new(7, 9));
+        assert_eq!(assignments.elves_at(4).len(), 7);
+        assert_eq!(
+            assignments.elves_overlapping(&Interval::new(9, 20)).len(),
+            1
+        );
+
+        let (max, sections) = assignments.max_concurrency();
+        assert_eq!(max, 8);
+        assert_eq!(
+            sections.iter().copied().collect::<Vec<_>>(),
+            vec![Interval::new(6, 6)]
+        );
+
+        let uncovered = assignments.uncovered(&Interval::new(1, 10));
+        let uncovered: Vec<_> = uncovered.iter().copied().collect();
+        assert_eq!(uncovered, vec![Interval::point(1), Interval::point(10)]);
+
+        let err = Assignments::parse("2-4,6-8\n2-3,x\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, AssignmentError::InvalidRange(2, _)));
+    }
+
+    #[test]
+    fn group_reports() {
+        let group = |ranges: &[(u32, u32)]| -> Vec<Range> {
+            ranges.iter().map(|&(s, e)| Interval::new(s, e)).collect()
+        };
+        let report = GroupReport::new(&group(&[(2, 8), (3, 7), (4, 9)]));
+        assert_eq!(report.container, None);
+        assert_eq!(report.intersection, Some(Interval::new(4, 7)));
+        assert_eq!(report.redundant, vec![1]);
+
+        let report = GroupReport::new(&group(&[(3, 4), (1, 9), (5, 5), (6, 7)]));
+        assert_eq!(report.container, Some(1));
+        assert_eq!(report.intersection, None);
+        assert_eq!(report.redundant, vec![0, 2, 3]);
+
+        // the union of the others counts, not any single one of them
+        let report = GroupReport::new(&group(&[(1, 3), (4, 6), (2, 5)]));
+        assert_eq!(report.redundant, vec![2]);
+
+        let report = GroupReport::new(&group(&[(1, 3), (1, 3)]));
+        assert_eq!(report.container, Some(0));
+        assert_eq!(report.redundant, vec![0, 1]);
+
+        let report = GroupReport::new(&[]);
+        assert_eq!((report.container, report.intersection), (None, None));
+    }
 }
@@ -4,7 +4,7 @@
 
 use std::io::{BufRead, BufReader, Read};
 
-use day05::{Command, CrateStacks};
+use day05::{CrateMover9000, CrateStacks, Plan};
 
 use rust_embed::RustEmbed;
 
@@ -16,31 +16,29 @@ fn process_and_peek_top_letters(reader: impl Read) -> Result<String, Box<dyn std
     let mut stack_lines = vec![];
     let mut command_lines = vec![];
     let mut separator_found = false;
-    for line_maybe in BufReader::new(reader).lines() {
+    for (idx, line_maybe) in BufReader::new(reader).lines().enumerate() {
         let line = line_maybe?;
         if line.is_empty() {
             separator_found = true;
         } else if separator_found {
-            command_lines.push(line);
+            command_lines.push((idx + 1, line));
         } else {
             stack_lines.push(line);
         }
     }
 
-    let mut stack = CrateStacks::from_text_repr(stack_lines);
-    let commands: Vec<Command> = command_lines
-        .iter()
-        .map(|line| line.as_str().into())
-        .collect();
-
-    for command in commands {
-        stack.apply(&command);
-    }
+    let mut stack = CrateStacks::from_text_repr(stack_lines)?;
+    let plan = Plan::parse(
+        command_lines
+            .iter()
+            .map(|(line_number, line)| (*line_number, line.as_str())),
+    )?;
+    plan.execute_atomically(&mut stack, &CrateMover9000)?;
 
     Ok(stack
         .peek_top_letters()
         .iter()
-        .map(|&e| e.unwrap_or(&('-')))
+        .map(|&e| e.unwrap_or("-"))
         .collect())
 }
 
@@ -1,18 +1,74 @@
-#![feature(lazy_cell)]
-
-use std::sync::LazyLock;
+use std::{fmt::Display, num::NonZeroUsize, str::FromStr, sync::LazyLock};
 
 use regex::Regex;
 
 static NUMBER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
 
 static COMMAND_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"move (?P<amount>\d+) from (?P<from>\d+) to (?P<to>\d+)").unwrap()
+    Regex::new(r"^move (?P<amount>\d+) from (?P<from>\d+) to (?P<to>\d+)$").unwrap()
 });
 
-#[derive(Debug)]
-pub struct CrateStacks {
-    stacks: Vec<Vec<char>>, // 0-based
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseStacksError {
+    MissingIndexLine,
+    /// 1-based line number of a `[` without a matching `]`
+    UnclosedCrate(usize),
+    /// 1-based line number and a crate not standing above exactly one free stack label
+    MisalignedCrate(usize, String),
+    /// 1-based line number and a crate with nothing below it
+    FloatingCrate(usize, String),
+    /// Stacks have to be numbered `1` to `N` from left to right
+    UnexpectedStackNumber {
+        expected: usize,
+        found: String,
+    },
+}
+
+impl Display for ParseStacksError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseStacksError::MissingIndexLine => write!(f, "Missing line with stack numbers"),
+            ParseStacksError::UnclosedCrate(line) => write!(f, "Line {line}: unclosed crate"),
+            ParseStacksError::MisalignedCrate(line, label) => {
+                write!(
+                    f,
+                    "Line {line}: crate [{label}] is not above a stack number"
+                )
+            }
+            ParseStacksError::FloatingCrate(line, label) => {
+                write!(f, "Line {line}: crate [{label}] has nothing below it")
+            }
+            ParseStacksError::UnexpectedStackNumber { expected, found } => {
+                write!(f, "Expected stack number {expected}, but found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseStacksError {}
+
+/// Byte offsets of the opening and closing brackets and the label of every crate in a line
+fn crate_spans(
+    line_number: usize,
+    line: &str,
+) -> Result<Vec<(usize, usize, &str)>, ParseStacksError> {
+    let mut result = vec![];
+    let mut rest_start = 0;
+    while let Some(open) = line[rest_start..].find('[').map(|idx| idx + rest_start) {
+        let close = line[open..]
+            .find(']')
+            .map(|idx| idx + open)
+            .ok_or(ParseStacksError::UnclosedCrate(line_number))?;
+        result.push((open, close, &line[open + 1..close]));
+        rest_start = close + 1;
+    }
+    Ok(result)
+}
+
+/// Crates are labels by default, but cranes can move anything, see [Journal]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateStacks<T = String> {
+    stacks: Vec<Vec<T>>, // 0-based
 }
 
 impl CrateStacks {
@@ -27,75 +83,985 @@ impl CrateStacks {
     //    [C] [N] [H] [R] [N] [H] [D] [J] [Q]
     //    [N] [D] [M] [G] [Z] [F] [W] [S] [S]
     //     1   2   3   4   5   6   7   8   9
-    pub fn from_text_repr(mut stacks_text: Vec<String>) -> CrateStacks {
-        let index_line = stacks_text.pop().unwrap();
-        stacks_text.reverse();
-        let mut stacks_vec = Vec::with_capacity(10);
-        for num_match in NUMBER_REGEX.find_iter(&index_line) {
-            let num = num_match.as_str();
-            let num_idx = index_line.find(num).unwrap();
-            let mut v = vec![];
-            for line in stacks_text.iter() {
-                if num_idx < line.len() {
-                    let c = line.as_bytes()[num_idx] as char;
-                    if c.is_ascii_alphabetic() {
-                        v.push(c);
+    //
+    // Crates may have longer labels, like `[AB]`, and there may be more than 9 stacks.
+    // A crate belongs to the stack whose number is (at least partially) right below it.
+    pub fn from_text_repr(mut stacks_text: Vec<String>) -> Result<CrateStacks, ParseStacksError> {
+        let index_line = stacks_text
+            .pop()
+            .ok_or(ParseStacksError::MissingIndexLine)?;
+        let mut labels: Vec<(usize, usize)> = vec![];
+        for (idx, num_match) in NUMBER_REGEX.find_iter(&index_line).enumerate() {
+            if num_match.as_str() != (idx + 1).to_string() {
+                return Err(ParseStacksError::UnexpectedStackNumber {
+                    expected: idx + 1,
+                    found: num_match.as_str().to_string(),
+                });
+            }
+            labels.push((num_match.start(), num_match.end() - 1));
+        }
+        if labels.is_empty() {
+            return Err(ParseStacksError::MissingIndexLine);
+        }
+
+        let mut stacks_vec: Vec<Vec<String>> = vec![vec![]; labels.len()];
+        for (row, (line_idx, line)) in stacks_text.iter().enumerate().rev().enumerate() {
+            let line_number = line_idx + 1;
+            for (open, close, label) in crate_spans(line_number, line)? {
+                let misaligned =
+                    || ParseStacksError::MisalignedCrate(line_number, label.to_string());
+                let mut below = labels
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &(start, end))| start <= close && open <= end)
+                    .map(|(stack, _)| stack);
+                let (Some(stack), None) = (below.next(), below.next()) else {
+                    return Err(misaligned());
+                };
+                match stacks_vec[stack].len() {
+                    len if len == row => stacks_vec[stack].push(label.to_string()),
+                    len if len > row => return Err(misaligned()),
+                    _ => {
+                        return Err(ParseStacksError::FloatingCrate(
+                            line_number,
+                            label.to_string(),
+                        ))
                     }
                 }
             }
-            stacks_vec.push(v);
         }
-        CrateStacks { stacks: stacks_vec }
+        Ok(CrateStacks { stacks: stacks_vec })
     }
 
-    pub fn apply(&mut self, command: &Command) {
-        for _ in 0..(command.amount) {
-            if let Some(item) = self.stacks.get_mut(command.from - 1).unwrap().pop() {
-                let target_stack = self.stacks.get_mut(command.to - 1).unwrap();
-                target_stack.push(item);
-            } else {
-                eprintln!("Unable to execute command: {:?}. Source is empty", command);
-            }
+    pub fn peek_top_letters(&self) -> Vec<Option<&str>> {
+        self.stacks
+            .iter()
+            .map(|vec| vec.last().map(String::as_str))
+            .collect()
+    }
+}
+
+impl<T> CrateStacks<T> {
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Crates of the stack with the given 1-based number, bottom first
+    pub fn stack(&self, stack: usize) -> &[T] {
+        &self.stacks[stack - 1]
+    }
+
+    /// Checks that `command` can be executed right now: both stacks exist, something is
+    /// moved and the source stack holds enough crates
+    pub fn validate(&self, command: &Command) -> Result<(), CommandError> {
+        if command.amount == 0 {
+            return Err(CommandError::ZeroAmount);
         }
+        let source = self.get(command.from)?;
+        self.get(command.to)?;
+        if source.len() < command.amount {
+            return Err(CommandError::InsufficientCrates {
+                stack: command.from,
+                available: source.len(),
+                requested: command.amount,
+            });
+        }
+        Ok(())
+    }
+
+    fn get(&self, stack: usize) -> Result<&Vec<T>, CommandError> {
+        stack
+            .checked_sub(1)
+            .and_then(|idx| self.stacks.get(idx))
+            .ok_or(CommandError::UnknownStack(stack))
     }
 
-    pub fn apply_preserve_order(&mut self, command: &Command) {
-        let drained: Vec<_> = {
-            let source_stack = self.stacks.get_mut(command.from - 1).unwrap();
-            let start_idx = source_stack.len() - command.amount;
-            source_stack.drain(start_idx..).collect()
+    fn get_mut(&mut self, stack: usize) -> Result<&mut Vec<T>, CommandError> {
+        stack
+            .checked_sub(1)
+            .and_then(|idx| self.stacks.get_mut(idx))
+            .ok_or(CommandError::UnknownStack(stack))
+    }
+
+    /// Removes `amount` crates from the top of the stack with the given 1-based number
+    /// and returns them in their original order, bottom first
+    pub fn take(&mut self, stack: usize, amount: usize) -> Result<Vec<T>, CommandError> {
+        let source_stack = self.get_mut(stack)?;
+        let Some(start_idx) = source_stack.len().checked_sub(amount) else {
+            return Err(CommandError::InsufficientCrates {
+                stack,
+                available: source_stack.len(),
+                requested: amount,
+            });
         };
-        if drained.len() != command.amount {
-            panic!(
-                "Unable to execute command `{:?}`: not enough items in the source stack",
-                drained
-            )
-        }
-        let target_stack = self.stacks.get_mut(command.to - 1).unwrap();
-        target_stack.extend(drained);
+        Ok(source_stack.drain(start_idx..).collect())
+    }
+
+    /// Puts crates on top of the stack with the given 1-based number, the first one goes lowest
+    pub fn put(
+        &mut self,
+        stack: usize,
+        crates: impl IntoIterator<Item = T>,
+    ) -> Result<(), CommandError> {
+        self.get_mut(stack)?.extend(crates);
+        Ok(())
+    }
+
+    /// Validates the command first, so a rejected command leaves the stacks untouched
+    pub fn apply_with(
+        &mut self,
+        crane: &impl Crane,
+        command: &Command,
+    ) -> Result<(), CommandError> {
+        self.validate(command)?;
+        crane.execute(self, command)
+    }
+
+    pub fn apply(&mut self, command: &Command) -> Result<(), CommandError> {
+        self.apply_with(&CrateMover9000, command)
     }
 
-    pub fn peek_top_letters(&self) -> Vec<Option<&char>> {
-        self.stacks.iter().map(|vec| vec.last()).collect()
+    pub fn apply_preserve_order(&mut self, command: &Command) -> Result<(), CommandError> {
+        self.apply_with(&CrateMover9001, command)
+    }
+}
+
+impl FromStr for CrateStacks {
+    type Err = ParseStacksError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect();
+        CrateStacks::from_text_repr(lines)
     }
 }
 
-#[derive(Debug)]
+impl Display for CrateStacks {
+    /// Renders the stacks the way `from_text_repr` expects them, with centered columns
+    /// as wide as the longest crate or stack number
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label_width = self.stacks.len().to_string().len();
+        let crate_width = self.stacks.iter().flatten().map(|c| c.chars().count() + 2);
+        let width = crate_width.fold(label_width + 2, usize::max);
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or_default();
+
+        for row in (0..height).rev() {
+            let cells: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(label) => format!("{:^width$}", format!("[{label}]")),
+                    None => " ".repeat(width),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        let labels: Vec<String> = (1..=self.stacks.len())
+            .map(|label| format!("{label:^width$}"))
+            .collect();
+        writeln!(f, "{}", labels.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Command {
     pub from: usize,
     pub to: usize,
     pub amount: usize,
 }
 
+impl FromStr for Command {
+    type Err = CommandError;
+
+    /// Parses smth like line below into a [Command]
+    ///
+    /// move 7 from 6 to 8
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CommandError::InvalidCommand(s.to_string());
+        let captures = COMMAND_REGEX.captures(s.trim()).ok_or_else(invalid)?;
+        let number = |name: &str| captures[name].parse::<usize>().map_err(|_| invalid());
+        Ok(Command {
+            from: number("from")?,
+            to: number("to")?,
+            amount: number("amount")?,
+        })
+    }
+}
+
 impl From<&str> for Command {
-    /// Convert smth like line below into a [Command]
+    /// Convert smth like line below into a [Command], panics if it is malformed
     ///
     /// move 7 from 6 to 8
     fn from(value_str: &str) -> Self {
-        let captures = COMMAND_REGEX.captures_iter(value_str).next().unwrap();
-        let amount: usize = captures.name("amount").unwrap().as_str().parse().unwrap();
-        let from: usize = captures.name("from").unwrap().as_str().parse().unwrap();
-        let to: usize = captures.name("to").unwrap().as_str().parse().unwrap();
-        Command { from, to, amount }
+        value_str.parse().unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CommandError {
+    InvalidCommand(String),
+    /// 1-based number of a stack that does not exist
+    UnknownStack(usize),
+    InsufficientCrates {
+        stack: usize,
+        available: usize,
+        requested: usize,
+    },
+    ZeroAmount,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::InvalidCommand(line) => write!(f, "Invalid command: {line}"),
+            CommandError::UnknownStack(stack) => write!(f, "There is no stack {stack}"),
+            CommandError::InsufficientCrates {
+                stack,
+                available,
+                requested,
+            } => write!(
+                f,
+                "Stack {stack} holds {available} crates, but {requested} are to be moved"
+            ),
+            CommandError::ZeroAmount => write!(f, "Command moves no crates"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// A [CommandError] together with the 1-based line number of the failed command
+#[derive(Debug, PartialEq, Eq)]
+pub struct PlanError {
+    pub line: usize,
+    pub error: CommandError,
+}
+
+impl Display for PlanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+/// Commands to be executed one after another, each with its 1-based line number
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Plan {
+    pub commands: Vec<(usize, Command)>,
+}
+
+impl Plan {
+    /// Parses numbered command lines, skipping blank ones
+    pub fn parse<'a>(lines: impl IntoIterator<Item = (usize, &'a str)>) -> Result<Plan, PlanError> {
+        let commands = lines
+            .into_iter()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line, text)| {
+                text.parse()
+                    .map(|command| (line, command))
+                    .map_err(|error| PlanError { line, error })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Plan { commands })
+    }
+
+    /// Executes the commands in order and stops at the first failing one,
+    /// the commands before it stay applied
+    pub fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        crane: &impl Crane,
+    ) -> Result<(), PlanError> {
+        for (line, command) in self.commands.iter() {
+            stacks
+                .apply_with(crane, command)
+                .map_err(|error| PlanError { line: *line, error })?;
+        }
+        Ok(())
+    }
+
+    /// Executes the whole plan on a copy of `stacks` and returns the final state
+    pub fn dry_run<T: Clone>(
+        &self,
+        stacks: &CrateStacks<T>,
+        crane: &impl Crane,
+    ) -> Result<CrateStacks<T>, PlanError> {
+        let mut result = stacks.clone();
+        self.execute(&mut result, crane)?;
+        Ok(result)
+    }
+
+    /// Like [Plan::execute], but leaves `stacks` untouched unless every command succeeds
+    pub fn execute_atomically<T: Clone>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        crane: &impl Crane,
+    ) -> Result<(), PlanError> {
+        *stacks = self.dry_run(stacks, crane)?;
+        Ok(())
+    }
+}
+
+/// A model of a crane moving crates between `CrateStacks`.
+/// It may only touch the stacks from `command.from` to `command.to`, both inclusive.
+pub trait Crane {
+    /// Only called with commands which passed [CrateStacks::validate]
+    fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        command: &Command,
+    ) -> Result<(), CommandError>;
+}
+
+/// Moves crates one at a time, so their order gets reversed
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        command: &Command,
+    ) -> Result<(), CommandError> {
+        for _ in 0..(command.amount) {
+            let lifted = stacks.take(command.from, 1)?;
+            stacks.put(command.to, lifted)?;
+        }
+        Ok(())
+    }
+}
+
+/// Moves all crates at once, preserving their order
+#[derive(Debug, Clone, Copy)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        command: &Command,
+    ) -> Result<(), CommandError> {
+        let lifted = stacks.take(command.from, command.amount)?;
+        stacks.put(command.to, lifted)
+    }
+}
+
+/// Moves at most `capacity` crates per lift, preserving their order within a lift
+#[derive(Debug, Clone, Copy)]
+pub struct CapacityLimited {
+    pub capacity: NonZeroUsize,
+}
+
+impl Crane for CapacityLimited {
+    fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        command: &Command,
+    ) -> Result<(), CommandError> {
+        let mut remaining = command.amount;
+        while remaining > 0 {
+            let lifted = stacks.take(command.from, remaining.min(self.capacity.get()))?;
+            remaining -= lifted.len();
+            stacks.put(command.to, lifted)?;
+        }
+        Ok(())
+    }
+}
+
+/// Moves all crates at once, but flips them over on the way
+#[derive(Debug, Clone, Copy)]
+pub struct ReverseOrder;
+
+impl Crane for ReverseOrder {
+    fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        command: &Command,
+    ) -> Result<(), CommandError> {
+        let lifted = stacks.take(command.from, command.amount)?;
+        stacks.put(command.to, lifted.into_iter().rev())
+    }
+}
+
+/// Can only reach neighbouring stacks, so crates are relayed through every stack
+/// in between, each hop done by the `inner` crane
+#[derive(Debug, Clone, Copy)]
+pub struct NeighbourOnly<C> {
+    pub inner: C,
+}
+
+impl<C: Crane> Crane for NeighbourOnly<C> {
+    fn execute<T>(
+        &self,
+        stacks: &mut CrateStacks<T>,
+        command: &Command,
+    ) -> Result<(), CommandError> {
+        let mut from = command.from;
+        while from != command.to {
+            let to = if command.to > from {
+                from + 1
+            } else {
+                from - 1
+            };
+            let hop = Command {
+                from,
+                to,
+                amount: command.amount,
+            };
+            self.inner.execute(stacks, &hop)?;
+            from = to;
+        }
+        Ok(())
+    }
+}
+
+/// Identity of a crate: where it stood in the initial stacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CrateId {
+    /// 1-based stack number
+    pub stack: usize,
+    /// 0-based position counted from the bottom of the stack
+    pub height: usize,
+}
+
+/// How a single stack changed: everything above the first `keep` crates was replaced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackChange {
+    /// 1-based stack number
+    pub stack: usize,
+    pub keep: usize,
+    pub before: Vec<CrateId>,
+    pub after: Vec<CrateId>,
+}
+
+/// A command together with its effect on the stacks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub command: Command,
+    pub changes: Vec<StackChange>,
+}
+
+impl Step {
+    /// `before` holds the stacks the command may touch, starting with the stack `first`
+    fn record(
+        command: &Command,
+        first: usize,
+        before: Vec<Vec<CrateId>>,
+        after: &CrateStacks<CrateId>,
+    ) -> Step {
+        let changes = before
+            .into_iter()
+            .zip(first..)
+            .filter(|(old, stack)| old.as_slice() != after.stack(*stack))
+            .map(|(old, stack)| {
+                let new = after.stack(stack);
+                let keep = old
+                    .iter()
+                    .zip(new.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                StackChange {
+                    stack,
+                    keep,
+                    before: old[keep..].to_vec(),
+                    after: new[keep..].to_vec(),
+                }
+            })
+            .collect();
+        Step {
+            command: command.clone(),
+            changes,
+        }
+    }
+
+    fn redo(&self, crates: &mut CrateStacks<CrateId>) {
+        for change in self.changes.iter() {
+            let stack = &mut crates.stacks[change.stack - 1];
+            stack.truncate(change.keep);
+            stack.extend_from_slice(&change.after);
+        }
+    }
+
+    fn undo(&self, crates: &mut CrateStacks<CrateId>) {
+        for change in self.changes.iter() {
+            let stack = &mut crates.stacks[change.stack - 1];
+            stack.truncate(change.keep);
+            stack.extend_from_slice(&change.before);
+        }
+    }
+}
+
+/// `CrateStacks` which remember every applied command, so that they can be undone and redone.
+///
+/// Commands are executed on crate identities rather than labels, so crates with
+/// the same label can be told apart.
+#[derive(Debug, Clone)]
+pub struct Journal<C> {
+    /// Labels of all crates, see [CrateId]
+    initial: CrateStacks,
+    crates: CrateStacks<CrateId>,
+    /// Labels of `crates`, kept in sync with them
+    stacks: CrateStacks,
+    crane: C,
+    steps: Vec<Step>,
+    /// Number of steps currently applied
+    position: usize,
+}
+
+impl<C: Crane> Journal<C> {
+    pub fn new(stacks: CrateStacks, crane: C) -> Self {
+        let crates = CrateStacks {
+            stacks: (1..=stacks.len())
+                .map(|stack| {
+                    (0..stacks.stack(stack).len())
+                        .map(|height| CrateId { stack, height })
+                        .collect()
+                })
+                .collect(),
+        };
+        Journal {
+            initial: stacks.clone(),
+            crates,
+            stacks,
+            crane,
+            steps: vec![],
+            position: 0,
+        }
+    }
+
+    pub fn stacks(&self) -> &CrateStacks {
+        &self.stacks
+    }
+
+    /// Current positions of all crates
+    pub fn crates(&self) -> &CrateStacks<CrateId> {
+        &self.crates
+    }
+
+    pub fn label(&self, id: CrateId) -> &str {
+        &self.initial.stack(id.stack)[id.height]
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Applies the command after the current step, dropping all undone steps.
+    /// A rejected command is not recorded and keeps the undone steps
+    pub fn apply(&mut self, command: &Command) -> Result<(), CommandError> {
+        self.crates.validate(command)?;
+        let first = command.from.min(command.to);
+        let before = (first..=command.from.max(command.to))
+            .map(|stack| self.crates.stack(stack).to_vec())
+            .collect();
+        self.crates.apply_with(&self.crane, command)?;
+        self.steps.truncate(self.position);
+        self.steps
+            .push(Step::record(command, first, before, &self.crates));
+        self.position += 1;
+        self.relabel(self.position - 1);
+        Ok(())
+    }
+
+    /// Updates the labels of the stacks changed by the step
+    fn relabel(&mut self, step: usize) {
+        for change in self.steps[step].changes.iter() {
+            let ids = &self.crates.stacks[change.stack - 1][change.keep..];
+            let labels = &mut self.stacks.stacks[change.stack - 1];
+            labels.truncate(change.keep);
+            labels.extend(
+                ids.iter()
+                    .map(|id| self.initial.stacks[id.stack - 1][id.height].clone()),
+            );
+        }
+    }
+
+    /// Returns `false` if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        self.steps[self.position].undo(&mut self.crates);
+        self.relabel(self.position);
+        true
+    }
+
+    /// Returns `false` if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        if self.position == self.steps.len() {
+            return false;
+        }
+        self.steps[self.position].redo(&mut self.crates);
+        self.relabel(self.position);
+        self.position += 1;
+        true
+    }
+
+    /// Undoes or redoes steps until exactly `step` of them are applied
+    pub fn go_to(&mut self, step: usize) {
+        let step = step.min(self.steps.len());
+        while self.position > step {
+            self.undo();
+        }
+        while self.position < step {
+            self.redo();
+        }
+    }
+
+    /// Crate positions after the first `step` commands, `0` being the initial state
+    pub fn crates_after(&self, step: usize) -> CrateStacks<CrateId> {
+        let mut crates = self.crates.clone();
+        let step = step.min(self.steps.len());
+        for undone in self.steps[step.min(self.position)..self.position]
+            .iter()
+            .rev()
+        {
+            undone.undo(&mut crates);
+        }
+        for redone in self.steps[self.position.min(step)..step].iter() {
+            redone.redo(&mut crates);
+        }
+        crates
+    }
+
+    /// Stacks after the first `step` commands, `0` being the initial state
+    pub fn state_after(&self, step: usize) -> CrateStacks {
+        CrateStacks {
+            stacks: self
+                .crates_after(step)
+                .stacks
+                .iter()
+                .map(|stack| stack.iter().map(|&id| self.label(id).to_string()).collect())
+                .collect(),
+        }
+    }
+
+    /// Number of the first step after which the crate is on top of a stack, and that stack
+    pub fn first_on_top(&self, id: CrateId) -> Option<(usize, usize)> {
+        let mut crates = self.crates_after(0);
+        for (idx, step) in self.steps.iter().enumerate() {
+            step.redo(&mut crates);
+            if let Some(stack) = crates.stacks.iter().position(|s| s.last() == Some(&id)) {
+                return Some((idx + 1, stack + 1));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks() -> CrateStacks {
+        CrateStacks::from_text_repr(
+            ["[A]        ", "[B]     [X]", "[C] [D] [Y]", " 1   2   3 "]
+                .map(String::from)
+                .to_vec(),
+        )
+        .unwrap()
+    }
+
+    fn contents(stacks: &CrateStacks) -> Vec<String> {
+        (1..=stacks.len())
+            .map(|stack| stacks.stack(stack).concat())
+            .collect()
+    }
+
+    #[test]
+    fn cranes() {
+        let command = Command::from("move 3 from 1 to 2");
+
+        let mut s = stacks();
+        s.apply(&command).unwrap();
+        assert_eq!(contents(&s), vec!["", "DABC", "YX"]);
+
+        let mut s = stacks();
+        s.apply_preserve_order(&command).unwrap();
+        assert_eq!(contents(&s), vec!["", "DCBA", "YX"]);
+
+        let mut s = stacks();
+        let capacity = NonZeroUsize::new(2).unwrap();
+        s.apply_with(&CapacityLimited { capacity }, &command)
+            .unwrap();
+        assert_eq!(contents(&s), vec!["", "DBAC", "YX"]);
+
+        let mut s = stacks();
+        s.apply_with(&ReverseOrder, &command).unwrap();
+        assert_eq!(contents(&s), vec!["", "DABC", "YX"]);
+
+        let command = Command::from("move 2 from 1 to 3");
+        let mut s = stacks();
+        s.apply_with(
+            &NeighbourOnly {
+                inner: CrateMover9000,
+            },
+            &command,
+        )
+        .unwrap();
+        assert_eq!(contents(&s), vec!["C", "D", "YXBA"]);
+        let mut s = stacks();
+        s.apply_with(
+            &NeighbourOnly {
+                inner: CrateMover9001,
+            },
+            &command,
+        )
+        .unwrap();
+        assert_eq!(contents(&s), vec!["C", "D", "YXBA"]);
+        let mut s = stacks();
+        s.apply_with(
+            &NeighbourOnly {
+                inner: ReverseOrder,
+            },
+            &command,
+        )
+        .unwrap();
+        assert_eq!(contents(&s), vec!["C", "D", "YXBA"]);
+    }
+
+    #[test]
+    fn journal() {
+        let mut journal = Journal::new(stacks(), CrateMover9000);
+        for command in [
+            "move 1 from 3 to 2",
+            "move 2 from 1 to 3",
+            "move 2 from 2 to 1",
+        ] {
+            journal.apply(&Command::from(command)).unwrap();
+        }
+        assert_eq!(contents(journal.stacks()), vec!["CXD", "", "YAB"]);
+        assert_eq!(contents(&journal.state_after(1)), vec!["CBA", "DX", "Y"]);
+        assert_eq!(journal.state_after(0), stacks());
+
+        assert!(journal.undo());
+        assert!(journal.undo());
+        assert_eq!(contents(journal.stacks()), vec!["CBA", "DX", "Y"]);
+        assert!(journal.redo());
+        assert_eq!(journal.position(), 2);
+        assert_eq!(journal.state_after(3), {
+            let mut j = journal.clone();
+            j.go_to(3);
+            j.stacks().clone()
+        });
+
+        // a new command drops the undone step
+        journal.apply(&Command::from("move 1 from 2 to 1")).unwrap();
+        assert_eq!(journal.steps().len(), 3);
+        assert!(!journal.redo());
+        journal.go_to(0);
+        assert_eq!(journal.stacks(), &stacks());
+        assert!(!journal.undo());
+
+        let id = |stack, height| CrateId { stack, height };
+        assert_eq!(journal.label(id(3, 1)), "X");
+        assert_eq!(journal.first_on_top(id(3, 1)), Some((1, 2)));
+        assert_eq!(journal.first_on_top(id(1, 1)), Some((2, 3)));
+        assert_eq!(journal.first_on_top(id(1, 0)), Some((2, 1)));
+        assert_eq!(journal.first_on_top(id(2, 0)), Some((3, 2)));
+        assert_eq!(journal.first_on_top(id(4, 0)), None);
+    }
+
+    #[test]
+    fn journal_identity() {
+        let stacks: CrateStacks = "[A]        \n[A] [A] [B]\n 1   2   3 ".parse().unwrap();
+        let mut journal = Journal::new(stacks, CrateMover9000);
+        journal.apply(&Command::from("move 1 from 1 to 2")).unwrap();
+        journal.apply(&Command::from("move 1 from 2 to 1")).unwrap();
+        assert_eq!(contents(journal.stacks()), vec!["AA", "A", "B"]);
+
+        let id = |stack, height| CrateId { stack, height };
+        // same labels, different crates
+        assert_eq!(journal.first_on_top(id(1, 1)), Some((1, 2)));
+        assert_eq!(journal.first_on_top(id(1, 0)), Some((1, 1)));
+        assert_eq!(journal.first_on_top(id(2, 0)), Some((2, 2)));
+        assert_eq!(journal.crates().stack(1), &[id(1, 0), id(1, 1)]);
+
+        // only the stacks between `from` and `to` are recorded
+        let step = &journal.steps()[0];
+        assert_eq!(
+            step.changes,
+            vec![
+                StackChange {
+                    stack: 1,
+                    keep: 1,
+                    before: vec![id(1, 1)],
+                    after: vec![]
+                },
+                StackChange {
+                    stack: 2,
+                    keep: 1,
+                    before: vec![],
+                    after: vec![id(1, 1)]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn command_errors() {
+        assert_eq!(
+            "move 1 from A to 2".parse::<Command>(),
+            Err(CommandError::InvalidCommand("move 1 from A to 2".into()))
+        );
+        assert_eq!(
+            "please move 1 from 2 to 3 and 4".parse::<Command>(),
+            Err(CommandError::InvalidCommand(
+                "please move 1 from 2 to 3 and 4".into()
+            ))
+        );
+        assert_eq!(
+            " move 1 from 2 to 3\r".parse::<Command>(),
+            Ok(Command {
+                from: 2,
+                to: 3,
+                amount: 1
+            })
+        );
+
+        let mut s = stacks();
+        let apply = |s: &mut CrateStacks, command| s.apply(&Command::from(command));
+        assert_eq!(
+            apply(&mut s, "move 1 from 4 to 1"),
+            Err(CommandError::UnknownStack(4))
+        );
+        assert_eq!(
+            apply(&mut s, "move 1 from 1 to 0"),
+            Err(CommandError::UnknownStack(0))
+        );
+        assert_eq!(
+            apply(&mut s, "move 0 from 1 to 2"),
+            Err(CommandError::ZeroAmount)
+        );
+        assert_eq!(
+            apply(&mut s, "move 2 from 2 to 1"),
+            Err(CommandError::InsufficientCrates {
+                stack: 2,
+                available: 1,
+                requested: 2
+            })
+        );
+        assert_eq!(s, stacks());
+
+        let mut journal = Journal::new(stacks(), CrateMover9001);
+        journal.apply(&Command::from("move 1 from 2 to 1")).unwrap();
+        journal.undo();
+        assert!(journal.apply(&Command::from("move 2 from 2 to 1")).is_err());
+        assert!(journal.redo());
+    }
+
+    #[test]
+    fn plans() {
+        let text = "move 1 from 2 to 1\n\nmove 2 from 3 to 2\nmove 3 from 2 to 3";
+        let plan =
+            Plan::parse(text.lines().enumerate().map(|(idx, line)| (idx + 5, line))).unwrap();
+        assert_eq!(plan.commands.len(), 3);
+        let failure = PlanError {
+            line: 8,
+            error: CommandError::InsufficientCrates {
+                stack: 2,
+                available: 2,
+                requested: 3,
+            },
+        };
+        assert_eq!(
+            failure.to_string(),
+            "Line 8: Stack 2 holds 2 crates, but 3 are to be moved"
+        );
+
+        assert_eq!(plan.dry_run(&stacks(), &CrateMover9000), Err(failure));
+        let mut s = stacks();
+        assert!(plan.execute_atomically(&mut s, &CrateMover9000).is_err());
+        assert_eq!(s, stacks());
+        assert!(plan.execute(&mut s, &CrateMover9000).is_err());
+        assert_eq!(contents(&s), vec!["CBAD", "XY", ""]);
+
+        let plan = Plan {
+            commands: plan.commands[..2].to_vec(),
+        };
+        let mut s = stacks();
+        plan.execute_atomically(&mut s, &CrateMover9000).unwrap();
+        assert_eq!(contents(&s), vec!["CBAD", "XY", ""]);
+        assert_eq!(
+            Plan::parse([(1, "move 1 from 2 to 1"), (2, "move one")]),
+            Err(PlanError {
+                line: 2,
+                error: CommandError::InvalidCommand("move one".into())
+            })
+        );
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let text = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+        let stacks: CrateStacks = text.parse().unwrap();
+        assert_eq!(contents(&stacks), vec!["ZN", "MCD", "P"]);
+        assert_eq!(stacks.to_string(), text);
+
+        let mut stacks = CrateStacks {
+            stacks: (0..11).map(|i| vec![format!("{i}"); i % 3]).collect(),
+        };
+        stacks.put(11, ["XYZ".to_string()]).unwrap();
+        let text = stacks.to_string();
+        assert!(text.ends_with("  10    11  \n"));
+        assert_eq!(text.parse::<CrateStacks>().unwrap(), stacks);
+
+        let text = "        [AB]\n[C]  [D]  [EF]\n 1    2    3\n";
+        let stacks: CrateStacks = text.parse().unwrap();
+        assert_eq!(contents(&stacks), vec!["C", "D", "EFAB"]);
+        assert_eq!(stacks.to_string().parse::<CrateStacks>().unwrap(), stacks);
+    }
+
+    #[test]
+    fn text_errors() {
+        assert_eq!(
+            "".parse::<CrateStacks>(),
+            Err(ParseStacksError::MissingIndexLine)
+        );
+        assert_eq!(
+            "[A]\n[B\n 1 ".parse::<CrateStacks>(),
+            Err(ParseStacksError::UnclosedCrate(2))
+        );
+        assert_eq!(
+            "[A]\n[B]   \n 1   2 ".parse::<CrateStacks>(),
+            Ok(CrateStacks {
+                stacks: vec![vec!["B".into(), "A".into()], vec![]]
+            })
+        );
+        assert_eq!(
+            "  [A]\n 1   2 ".parse::<CrateStacks>(),
+            Err(ParseStacksError::MisalignedCrate(1, "A".into()))
+        );
+        assert_eq!(
+            "    [A]\n[B]    \n 1   2 ".parse::<CrateStacks>(),
+            Err(ParseStacksError::FloatingCrate(1, "A".into()))
+        );
+        assert_eq!(
+            "[A] [B]\n 1   5 ".parse::<CrateStacks>(),
+            Err(ParseStacksError::UnexpectedStackNumber {
+                expected: 2,
+                found: "5".into()
+            })
+        );
+        assert_eq!(
+            "[A] [B]\n 2   1 ".parse::<CrateStacks>(),
+            Err(ParseStacksError::UnexpectedStackNumber {
+                expected: 1,
+                found: "2".into()
+            })
+        );
     }
 }
@@ -9,6 +9,20 @@ impl Pos {
         Pos { x, y }
     }
 
+    /// Position shifted by `dx` and `dy`
+    pub fn offset(&self, dx: i32, dy: i32) -> Pos {
+        Pos::new(self.x + dx, self.y + dy)
+    }
+
+    /// All 8 surrounding positions
+    pub fn adjacent(&self) -> impl Iterator<Item = Pos> {
+        let pos = *self;
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+            .map(move |(dx, dy)| pos.offset(dx, dy))
+    }
+
     pub fn dist(&self, other: &Pos) -> usize {
         i32::max(i32::abs(self.x - other.x), i32::abs(self.y - other.y)) as usize
     }
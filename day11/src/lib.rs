@@ -1,23 +1,183 @@
 use std::{
     collections::HashMap,
     io::{BufRead, BufReader, Read},
+    ops::{Add, Div, Mul, Rem, Sub},
     str::FromStr,
 };
 
-enum Operand {
+pub enum Operand {
     Argument,
     IntValue(u128),
 }
 
-enum BinaryOperator {
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BinaryOperator {
     Add,
+    Subtract,
     Multiply,
+    Divide,
 }
 
-pub struct Operation {
-    left_operand: Operand,
-    right_operand: Operand,
-    binary_operator: BinaryOperator,
+impl BinaryOperator {
+    pub fn parse(op_str: &str) -> Self {
+        match op_str {
+            "+" => BinaryOperator::Add,
+            "-" => BinaryOperator::Subtract,
+            "*" => BinaryOperator::Multiply,
+            "/" => BinaryOperator::Divide,
+            _ => panic!("Unexpected binary operator found: {}", op_str),
+        }
+    }
+
+    pub fn apply<T>(&self, left: T, right: T) -> T
+    where
+        T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+    {
+        match self {
+            BinaryOperator::Add => left + right,
+            BinaryOperator::Subtract => left - right,
+            BinaryOperator::Multiply => left * right,
+            BinaryOperator::Divide => left / right,
+        }
+    }
+
+    /// Finds `left` such that `left <op> right == result`, where division truncates.
+    /// Returns `None` if there is no such integer or it is not unique.
+    pub fn solve_for_left<T>(&self, result: T, right: T) -> Option<T>
+    where
+        T: Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Rem<Output = T>
+            + From<i8>
+            + Default
+            + PartialEq
+            + Copy,
+    {
+        match self {
+            BinaryOperator::Add => Some(result - right),
+            BinaryOperator::Subtract => Some(result + right),
+            BinaryOperator::Multiply => exact_div(result, right),
+            // every divisor but ±1 maps several dividends onto the same quotient
+            BinaryOperator::Divide if right == T::from(1) || right == T::from(-1) => {
+                Some(result * right)
+            }
+            BinaryOperator::Divide => None,
+        }
+    }
+
+    /// Finds `right` such that `left <op> right == result`, where division truncates.
+    /// Returns `None` if there is no such integer or it is not unique.
+    pub fn solve_for_right<T>(&self, left: T, result: T) -> Option<T>
+    where
+        T: Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Rem<Output = T>
+            + From<i8>
+            + Default
+            + PartialOrd
+            + Copy,
+    {
+        match self {
+            BinaryOperator::Add => Some(result - left),
+            BinaryOperator::Subtract => Some(left - result),
+            BinaryOperator::Multiply => exact_div(result, left),
+            BinaryOperator::Divide => truncating_divisor(left, result),
+        }
+    }
+
+    /// Like [BinaryOperator::solve_for_left], but a division has to leave no remainder
+    pub fn solve_exactly_for_left<T>(&self, result: T, right: T) -> Option<T>
+    where
+        T: Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Rem<Output = T>
+            + Default
+            + PartialEq
+            + Copy,
+    {
+        match self {
+            BinaryOperator::Add => Some(result - right),
+            BinaryOperator::Subtract => Some(result + right),
+            BinaryOperator::Multiply => exact_div(result, right),
+            BinaryOperator::Divide if right == T::default() => None,
+            BinaryOperator::Divide => Some(result * right),
+        }
+    }
+
+    /// Like [BinaryOperator::solve_for_right], but a division has to leave no remainder
+    pub fn solve_exactly_for_right<T>(&self, left: T, result: T) -> Option<T>
+    where
+        T: Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Rem<Output = T>
+            + Default
+            + PartialEq
+            + Copy,
+    {
+        match self {
+            BinaryOperator::Add => Some(result - left),
+            BinaryOperator::Subtract => Some(left - result),
+            BinaryOperator::Multiply => exact_div(result, left),
+            BinaryOperator::Divide => exact_div(left, result),
+        }
+    }
+}
+
+/// The only `divisor` with `dividend / divisor == quotient`, if there is exactly one
+fn truncating_divisor<T>(dividend: T, quotient: T) -> Option<T>
+where
+    T: Sub<Output = T> + Add<Output = T> + Div<Output = T> + From<i8> + Default + PartialOrd + Copy,
+{
+    let zero = T::default();
+    // any divisor larger than the dividend gives zero
+    if quotient == zero {
+        return None;
+    }
+    // the quotient only grows as the divisor shrinks towards zero, so the solutions
+    // are a contiguous range ending in the divisor furthest from zero
+    let furthest = dividend / quotient;
+    if furthest == zero || dividend / furthest != quotient {
+        return None;
+    }
+    let next = if furthest > zero {
+        furthest - T::from(1)
+    } else {
+        furthest + T::from(1)
+    };
+    if next != zero && dividend / next == quotient {
+        None
+    } else {
+        Some(furthest)
+    }
+}
+
+/// `dividend / divisor` if it leaves no remainder and the divisor is not zero
+fn exact_div<T>(dividend: T, divisor: T) -> Option<T>
+where
+    T: Div<Output = T> + Rem<Output = T> + Default + PartialEq + Copy,
+{
+    if divisor == T::default() || dividend % divisor != T::default() {
+        None
+    } else {
+        Some(dividend / divisor)
+    }
+}
+
+/// Binary operation over two operands. By default operands are either the argument
+/// (`old`) or a constant, but any other kind of operand (e.g. a reference to
+/// another expression) can be plugged in instead.
+pub struct Operation<O = Operand> {
+    pub left_operand: O,
+    pub right_operand: O,
+    pub binary_operator: BinaryOperator,
 }
 
 impl Operation {
@@ -30,10 +190,7 @@ impl Operation {
             Operand::Argument => arg,
             Operand::IntValue(val) => val,
         };
-        match self.binary_operator {
-            BinaryOperator::Add => left + right,
-            BinaryOperator::Multiply => left * right,
-        }
+        self.binary_operator.apply(left, right)
     }
 
     pub fn parse(op_str: &str) -> Self {
@@ -48,11 +205,7 @@ impl Operation {
             s => Operand::IntValue(u128::from_str(s).unwrap()),
         };
 
-        let binary_operator: BinaryOperator = match parts[1] {
-            "+" => BinaryOperator::Add,
-            "*" => BinaryOperator::Multiply,
-            _ => panic!("Unexpected binary operator found: {}", parts[1]),
-        };
+        let binary_operator = BinaryOperator::parse(parts[1]);
 
         Operation {
             left_operand,
@@ -70,7 +223,7 @@ pub struct DivisibleByTest {
 
 impl DivisibleByTest {
     fn next_monkey_index(&self, stress_level: u128) -> usize {
-        match stress_level % self.divisor == 0 {
+        match stress_level.is_multiple_of(self.divisor) {
             true => self.true_idx,
             false => self.false_idx,
         }
@@ -169,7 +322,7 @@ pub fn parse_all_monkeys(reader: impl Read) -> Vec<Monkey> {
 }
 
 pub fn process_round(
-    monkeys: &mut Vec<Monkey>,
+    monkeys: &mut [Monkey],
     decrease_stress_level: bool,
     common_divisor: Option<u128>,
 ) {
@@ -3,7 +3,7 @@ use std::{
     io::{BufRead, BufReader, Read},
 };
 
-#[derive(PartialEq, Eq, Debug, Default, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Default, Clone, Copy)]
 pub struct Point {
     pub row: usize,
     pub col: usize,
@@ -91,11 +91,11 @@ impl Topology {
     }
 
     pub fn get_start(&self) -> Point {
-        self.start.clone()
+        self.start
     }
 
     pub fn get_end(&self) -> Point {
-        self.end.clone()
+        self.end
     }
 
     pub fn rows(&self) -> usize {
@@ -2,10 +2,11 @@
 
 #![allow(non_upper_case_globals)]
 
+use day04::{Interval, IntervalSet};
 use day15::{manhattan_dist, Pos, COORDS_REGEX};
 use rust_embed::RustEmbed;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     io::{BufRead, BufReader, Read},
 };
 
@@ -36,13 +37,7 @@ impl Map {
 
 fn ruled_out_beacon_pos(reader: impl Read, target_y: i32) -> usize {
     let map = Map::parse(reader);
-    let known_beacons_on_target = map
-        .sensors_to_beacons
-        .values()
-        .filter_map(|pos| if pos.y == target_y { Some(pos.x) } else { None })
-        .collect::<HashSet<_>>();
-
-    let mut ruled_out_x = HashSet::new();
+    let mut ruled_out_x = IntervalSet::new();
     for (sensor, beacon) in map.sensors_to_beacons.iter() {
         let sensor_to_beacon_dist = manhattan_dist(sensor, beacon);
         let sensor_to_target_dist = sensor.y.abs_diff(target_y);
@@ -50,13 +45,14 @@ fn ruled_out_beacon_pos(reader: impl Read, target_y: i32) -> usize {
             continue;
         }
         let delta = (sensor_to_beacon_dist - sensor_to_target_dist) as i32;
-        for x in (sensor.x - delta)..=(sensor.x + delta) {
-            if known_beacons_on_target.get(&x).is_none() {
-                ruled_out_x.insert(x);
-            }
+        ruled_out_x.insert(Interval::new(sensor.x - delta, sensor.x + delta));
+    }
+    for beacon in map.sensors_to_beacons.values() {
+        if beacon.y == target_y {
+            ruled_out_x.remove(&Interval::point(beacon.x));
         }
     }
-    ruled_out_x.len()
+    ruled_out_x.covered_length() as usize
 }
 
 fn main() {
@@ -1,5 +1,3 @@
-#![feature(lazy_cell)]
-
 use std::{fmt::Display, sync::LazyLock};
 
 use regex::Regex;
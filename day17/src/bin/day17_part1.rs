@@ -0,0 +1,46 @@
+/*! See https://adventofcode.com/2022/day/17 */
+
+#![allow(non_upper_case_globals)]
+
+use day17::{parse_jets, Chamber};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn tower_height(reader: impl Read, total_rocks: usize) -> usize {
+    let mut chamber = Chamber::new(parse_jets(reader));
+    for _ in 0..total_rocks {
+        chamber.drop_rock();
+    }
+    chamber.height
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day17/input.txt")?;
+    let tower_height = tower_height(asset.data.as_ref(), 2022);
+    println!("Tower height after 2022 rocks: {tower_height}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(tower_height(asset.data.as_ref(), 2022), 3068);
+    }
+
+    #[test]
+    fn test_input_first_rocks() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(tower_height(asset.data.as_ref(), 1), 1);
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(tower_height(asset.data.as_ref(), 10), 17);
+    }
+}
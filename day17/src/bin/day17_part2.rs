@@ -0,0 +1,72 @@
+/*! See https://adventofcode.com/2022/day/17 */
+
+#![allow(non_upper_case_globals)]
+
+use day17::{parse_jets, Chamber, StateFingerprint};
+use rust_embed::RustEmbed;
+use std::{collections::HashMap, io::Read};
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn tower_height_with_cycle_detection(reader: impl Read, total_rocks: usize) -> usize {
+    let mut chamber = Chamber::new(parse_jets(reader));
+
+    // Remembers how many rocks were dropped and how high the tower was
+    // the last time the chamber was in a given state.
+    let mut seen: HashMap<StateFingerprint, (usize, usize)> = HashMap::new();
+    let mut skipped_height: Option<usize> = None;
+
+    while chamber.rocks_dropped < total_rocks {
+        chamber.drop_rock();
+        if skipped_height.is_some() {
+            // cycle has already been skipped, just simulate the remainder
+            continue;
+        }
+        let fingerprint = chamber.state_fingerprint();
+        if let Some(&(prev_rocks, prev_height)) = seen.get(&fingerprint) {
+            // The same state means the tower keeps growing in exactly the same way,
+            // so all full cycles that fit into the remaining rocks can be skipped at once.
+            let cycle_rocks = chamber.rocks_dropped - prev_rocks;
+            let cycle_height = chamber.height - prev_height;
+            let cycles = (total_rocks - chamber.rocks_dropped) / cycle_rocks;
+            skipped_height = Some(cycles * cycle_height);
+            chamber.rocks_dropped += cycles * cycle_rocks;
+        } else {
+            seen.insert(fingerprint, (chamber.rocks_dropped, chamber.height));
+        }
+    }
+    chamber.height + skipped_height.unwrap_or(0)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day17/input.txt")?;
+    let tower_height = tower_height_with_cycle_detection(asset.data.as_ref(), 1_000_000_000_000);
+    println!("Tower height after 1000000000000 rocks: {tower_height}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(
+            tower_height_with_cycle_detection(asset.data.as_ref(), 1_000_000_000_000),
+            1514285714288
+        );
+    }
+
+    #[test]
+    fn test_input_matches_plain_simulation() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(
+            tower_height_with_cycle_detection(asset.data.as_ref(), 2022),
+            3068
+        );
+    }
+}
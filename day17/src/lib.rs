@@ -0,0 +1,137 @@
+use std::{collections::HashMap, io::Read};
+
+pub use day14::{Cell, Pos};
+
+pub const CHAMBER_WIDTH: usize = 7;
+
+/// Rock shapes in the order they fall. Each shape is a list of `(dx, dy)` offsets
+/// from its bottom-left corner, `y` grows upwards.
+pub const ROCK_SHAPES: [&[(usize, usize)]; 5] = [
+    // ####
+    &[(0, 0), (1, 0), (2, 0), (3, 0)],
+    // .#.
+    // ###
+    // .#.
+    &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+    // ..#
+    // ..#
+    // ###
+    &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+    // #
+    // #
+    // #
+    // #
+    &[(0, 0), (0, 1), (0, 2), (0, 3)],
+    // ##
+    // ##
+    &[(0, 0), (1, 0), (0, 1), (1, 1)],
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Jet {
+    Left,
+    Right,
+}
+
+impl From<char> for Jet {
+    fn from(value: char) -> Self {
+        match value {
+            '<' => Jet::Left,
+            '>' => Jet::Right,
+            _ => panic!("Unsupported jet direction: '{value}'"),
+        }
+    }
+}
+
+/// Parses a single line of jets like `>>><<><>><<<>><>>><<<>>>`
+pub fn parse_jets(mut reader: impl Read) -> Vec<Jet> {
+    let mut buffer = String::new();
+    reader.read_to_string(&mut buffer).unwrap();
+    buffer.trim().chars().map(Jet::from).collect()
+}
+
+/// Everything that determines how the rest of the tower is going to grow:
+/// the next rock shape, the next jet and the skyline of the top of the tower
+/// (the depth of every column measured from the highest rock).
+pub type StateFingerprint = (usize, usize, [usize; CHAMBER_WIDTH]);
+
+pub struct Chamber {
+    pub cells: HashMap<Pos, Cell>,
+    pub height: usize,
+    pub rocks_dropped: usize,
+    column_heights: [usize; CHAMBER_WIDTH],
+    jets: Vec<Jet>,
+    jet_idx: usize,
+}
+
+impl Chamber {
+    pub fn new(jets: Vec<Jet>) -> Self {
+        assert!(!jets.is_empty(), "At least one jet is required");
+        Chamber {
+            cells: HashMap::new(),
+            height: 0,
+            rocks_dropped: 0,
+            column_heights: [0; CHAMBER_WIDTH],
+            jets,
+            jet_idx: 0,
+        }
+    }
+
+    pub fn get(&self, pos: &Pos) -> &Cell {
+        if let Some(cell) = self.cells.get(pos) {
+            return cell;
+        }
+        &Cell::Empty
+    }
+
+    fn fits(&self, shape: &[(usize, usize)], x: usize, y: usize) -> bool {
+        shape.iter().all(|&(dx, dy)| {
+            x + dx < CHAMBER_WIDTH && *self.get(&Pos::new(x + dx, y + dy)) == Cell::Empty
+        })
+    }
+
+    /// Drops the next rock, alternating between being pushed by a jet and falling
+    /// one unit down, until the rock comes to rest.
+    pub fn drop_rock(&mut self) {
+        let shape = ROCK_SHAPES[self.rocks_dropped % ROCK_SHAPES.len()];
+        let mut x: usize = 2;
+        let mut y = self.height + 3;
+
+        loop {
+            let jet = self.jets[self.jet_idx];
+            self.jet_idx = (self.jet_idx + 1) % self.jets.len();
+
+            let pushed_x = match jet {
+                Jet::Left => x.checked_sub(1),
+                Jet::Right => Some(x + 1),
+            };
+            if let Some(pushed_x) = pushed_x {
+                if self.fits(shape, pushed_x, y) {
+                    x = pushed_x;
+                }
+            }
+
+            if y > 0 && self.fits(shape, x, y - 1) {
+                y -= 1;
+            } else {
+                break;
+            }
+        }
+
+        for &(dx, dy) in shape {
+            let pos = Pos::new(x + dx, y + dy);
+            self.height = usize::max(self.height, pos.y + 1);
+            self.column_heights[pos.x] = usize::max(self.column_heights[pos.x], pos.y + 1);
+            self.cells.insert(pos, Cell::Rock);
+        }
+        self.rocks_dropped += 1;
+    }
+
+    pub fn state_fingerprint(&self) -> StateFingerprint {
+        (
+            self.rocks_dropped % ROCK_SHAPES.len(),
+            self.jet_idx,
+            self.column_heights.map(|column_height| self.height - column_height),
+        )
+    }
+}
@@ -0,0 +1,34 @@
+/*! See https://adventofcode.com/2022/day/18 */
+
+#![allow(non_upper_case_globals)]
+
+use day18::{exterior_surface_area, VoxelGrid};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn total_exterior_surface_area(reader: impl Read) -> usize {
+    exterior_surface_area(&VoxelGrid::parse(reader))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day18/input.txt")?;
+    let surface_area = total_exterior_surface_area(asset.data.as_ref());
+    println!("Exterior surface area: {surface_area}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(total_exterior_surface_area(asset.data.as_ref()), 58);
+    }
+}
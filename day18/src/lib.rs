@@ -0,0 +1,188 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{BufRead, BufReader, Read},
+};
+
+#[derive(PartialEq, Eq, Hash, Debug, Default, Clone, Copy)]
+pub struct Point3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point3 {
+    pub fn new(x: i32, y: i32, z: i32) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    /// Returns the 6 points sharing a face with this one
+    pub fn neighbours(&self) -> [Point3; 6] {
+        let Point3 { x, y, z } = *self;
+        [
+            Point3::new(x - 1, y, z),
+            Point3::new(x + 1, y, z),
+            Point3::new(x, y - 1, z),
+            Point3::new(x, y + 1, z),
+            Point3::new(x, y, z - 1),
+            Point3::new(x, y, z + 1),
+        ]
+    }
+}
+
+/// Inclusive axis-aligned box
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Bounds {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Bounds {
+    pub fn contains(&self, p: &Point3) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    pub fn expand(&self, by: i32) -> Bounds {
+        Bounds {
+            min: Point3::new(self.min.x - by, self.min.y - by, self.min.z - by),
+            max: Point3::new(self.max.x + by, self.max.y + by, self.max.z + by),
+        }
+    }
+}
+
+/// Sparse 3D grid of unit cubes
+#[derive(Debug, Default, Clone)]
+pub struct VoxelGrid {
+    pub voxels: HashSet<Point3>,
+}
+
+impl VoxelGrid {
+    /// Parses files of strings like:
+    /// 2,2,2
+    /// 1,2,2
+    pub fn parse(reader: impl Read) -> VoxelGrid {
+        let mut voxels = HashSet::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line.unwrap();
+            let coords: Vec<i32> = line
+                .split(',')
+                .map(|coord| str::parse(coord.trim()).unwrap())
+                .collect();
+            if coords.len() != 3 {
+                panic!("Expected cubes to be in format `x,y,z`, but found: {line}")
+            }
+            voxels.insert(Point3::new(coords[0], coords[1], coords[2]));
+        }
+        VoxelGrid { voxels }
+    }
+
+    pub fn is_occupied(&self, p: &Point3) -> bool {
+        self.voxels.contains(p)
+    }
+
+    /// Returns the smallest box containing all the voxels, if any
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut voxels = self.voxels.iter();
+        let first = *voxels.next()?;
+        let mut bounds = Bounds {
+            min: first,
+            max: first,
+        };
+        for p in voxels {
+            bounds.min = Point3::new(
+                i32::min(bounds.min.x, p.x),
+                i32::min(bounds.min.y, p.y),
+                i32::min(bounds.min.z, p.z),
+            );
+            bounds.max = Point3::new(
+                i32::max(bounds.max.x, p.x),
+                i32::max(bounds.max.y, p.y),
+                i32::max(bounds.max.z, p.z),
+            );
+        }
+        Some(bounds)
+    }
+
+    /// Counts the faces of all voxels that are not shared with another voxel
+    pub fn surface_area(&self) -> usize {
+        self.voxels
+            .iter()
+            .flat_map(|p| p.neighbours())
+            .filter(|n| !self.is_occupied(n))
+            .count()
+    }
+}
+
+/// Collects all empty points reachable from `start` via faces without leaving `bounds`.
+/// Works level by level, the same way as `day12::shortest_path`.
+pub fn flood_fill(grid: &VoxelGrid, start: Point3, bounds: &Bounds) -> HashSet<Point3> {
+    let mut visited = HashSet::new();
+    if grid.is_occupied(&start) || !bounds.contains(&start) {
+        return visited;
+    }
+
+    let mut next_queue = VecDeque::from([start]);
+    let mut queue;
+
+    while !next_queue.is_empty() {
+        queue = next_queue;
+        next_queue = VecDeque::new();
+
+        while let Some(p) = queue.pop_front() {
+            if !visited.insert(p) {
+                continue;
+            }
+            for n in p.neighbours() {
+                if !visited.contains(&n) && bounds.contains(&n) && !grid.is_occupied(&n) {
+                    next_queue.push_back(n);
+                }
+            }
+        }
+    }
+    visited
+}
+
+/// Counts only the faces that can be reached from outside of the grid,
+/// i.e. ignores the faces of any trapped air pockets.
+pub fn exterior_surface_area(grid: &VoxelGrid) -> usize {
+    let bounds = match grid.bounds() {
+        Some(bounds) => bounds.expand(1),
+        None => return 0,
+    };
+    let exterior = flood_fill(grid, bounds.min, &bounds);
+    grid.voxels
+        .iter()
+        .flat_map(|p| p.neighbours())
+        .filter(|n| exterior.contains(n))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_adjacent_cubes() {
+        let grid = VoxelGrid::parse("1,1,1\n2,1,1".as_bytes());
+        assert_eq!(grid.surface_area(), 10);
+        assert_eq!(exterior_surface_area(&grid), 10);
+    }
+
+    #[test]
+    fn hollow_cube_has_no_exterior_access_inside() {
+        let mut voxels = HashSet::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                for z in 0..3 {
+                    if (x, y, z) != (1, 1, 1) {
+                        voxels.insert(Point3::new(x, y, z));
+                    }
+                }
+            }
+        }
+        let grid = VoxelGrid { voxels };
+        assert_eq!(grid.surface_area(), 54 + 6);
+        assert_eq!(exterior_surface_area(&grid), 54);
+    }
+}
@@ -0,0 +1,45 @@
+/*! See https://adventofcode.com/2022/day/19 */
+
+#![allow(non_upper_case_globals)]
+
+use day19::{max_geodes, parse_blueprints};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn sum_quality_levels(reader: impl Read, time_budget: usize) -> usize {
+    parse_blueprints(reader)
+        .iter()
+        .map(|blueprint| blueprint.id * max_geodes(blueprint, time_budget))
+        .sum()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day19/input.txt")?;
+    let quality_levels = sum_quality_levels(asset.data.as_ref(), 24);
+    println!("Sum of quality levels: {quality_levels}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(sum_quality_levels(asset.data.as_ref(), 24), 33);
+    }
+
+    #[test]
+    fn test_input_max_geodes() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        let blueprints = parse_blueprints(asset.data.as_ref());
+        assert_eq!(max_geodes(&blueprints[0], 24), 9);
+        assert_eq!(max_geodes(&blueprints[1], 24), 12);
+    }
+}
@@ -0,0 +1,45 @@
+/*! See https://adventofcode.com/2022/day/19 */
+
+#![allow(non_upper_case_globals)]
+
+use day19::{max_geodes, parse_blueprints};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn multiply_max_geodes_of_first(
+    reader: impl Read,
+    blueprints_count: usize,
+    time_budget: usize,
+) -> usize {
+    parse_blueprints(reader)
+        .iter()
+        .take(blueprints_count)
+        .map(|blueprint| max_geodes(blueprint, time_budget))
+        .product()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day19/input.txt")?;
+    let geodes = multiply_max_geodes_of_first(asset.data.as_ref(), 3, 32);
+    println!("Max geodes of the first 3 blueprints multiplied: {geodes}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(
+            multiply_max_geodes_of_first(asset.data.as_ref(), 3, 32),
+            56 * 62
+        );
+    }
+}
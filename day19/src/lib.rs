@@ -0,0 +1,183 @@
+use std::{collections::HashSet, hash::Hash, io::Read, sync::LazyLock};
+
+use regex::Regex;
+
+// Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+pub static BLUEPRINT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Blueprint (?P<id>\d+):\s+Each ore robot costs (?P<ore_robot_ore>\d+) ore.\s+Each clay robot costs (?P<clay_robot_ore>\d+) ore.\s+Each obsidian robot costs (?P<obsidian_robot_ore>\d+) ore and (?P<obsidian_robot_clay>\d+) clay.\s+Each geode robot costs (?P<geode_robot_ore>\d+) ore and (?P<geode_robot_obsidian>\d+) obsidian.").unwrap()
+});
+
+/// A state of a search problem that maximizes some value, see [maximize]
+pub trait SearchState: Clone + Eq + Hash {
+    /// The value that is guaranteed to be achieved from this state,
+    /// even if nothing else is done.
+    fn value(&self) -> usize;
+
+    /// An optimistic estimation: no successor may ever achieve more than this.
+    fn upper_bound(&self) -> usize;
+
+    fn successors(&self) -> Vec<Self>;
+}
+
+/// Depth-first branch-and-bound search:
+/// - a branch is pruned as soon as its upper bound cannot beat the best value found so far
+/// - states that were already explored are memoized and never explored twice
+pub fn maximize<S: SearchState>(initial: S) -> usize {
+    fn visit<S: SearchState>(state: S, best: &mut usize, seen: &mut HashSet<S>) {
+        *best = usize::max(*best, state.value());
+        if state.upper_bound() <= *best || seen.contains(&state) {
+            return;
+        }
+        for next in state.successors() {
+            visit(next, best, seen);
+        }
+        seen.insert(state);
+    }
+
+    let mut best = 0;
+    visit(initial, &mut best, &mut HashSet::new());
+    best
+}
+
+pub const ORE: usize = 0;
+pub const CLAY: usize = 1;
+pub const OBSIDIAN: usize = 2;
+pub const GEODE: usize = 3;
+
+pub type Resources = [usize; 4];
+
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct Blueprint {
+    pub id: usize,
+    /// Cost of a robot collecting the resource at the given index
+    pub robot_costs: [Resources; 4],
+}
+
+impl Blueprint {
+    /// No point in building more robots of some kind than can be spent per minute
+    fn max_useful_robots(&self) -> Resources {
+        let mut max = [0, 0, 0, usize::MAX];
+        for cost in self.robot_costs.iter() {
+            for resource in [ORE, CLAY, OBSIDIAN] {
+                max[resource] = usize::max(max[resource], cost[resource]);
+            }
+        }
+        max
+    }
+}
+
+pub fn parse_blueprints(mut reader: impl Read) -> Vec<Blueprint> {
+    let mut buffer = String::new();
+    reader.read_to_string(&mut buffer).unwrap();
+
+    BLUEPRINT_REGEX
+        .captures_iter(&buffer)
+        .map(|captures| {
+            let get =
+                |name: &str| -> usize { captures.name(name).unwrap().as_str().parse().unwrap() };
+            Blueprint {
+                id: get("id"),
+                robot_costs: [
+                    [get("ore_robot_ore"), 0, 0, 0],
+                    [get("clay_robot_ore"), 0, 0, 0],
+                    [get("obsidian_robot_ore"), get("obsidian_robot_clay"), 0, 0],
+                    [get("geode_robot_ore"), 0, get("geode_robot_obsidian"), 0],
+                ],
+            }
+        })
+        .collect()
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Debug)]
+pub struct Factory<'a> {
+    blueprint: &'a Blueprint,
+    time_left: usize,
+    robots: Resources,
+    resources: Resources,
+}
+
+impl<'a> Factory<'a> {
+    /// Starts with a single ore-collecting robot
+    pub fn new(blueprint: &'a Blueprint, time_left: usize) -> Self {
+        Factory {
+            blueprint,
+            time_left,
+            robots: [1, 0, 0, 0],
+            resources: [0; 4],
+        }
+    }
+
+    /// Minutes to wait until the robot can be afforded, if ever
+    fn wait_time(&self, robot: usize) -> Option<usize> {
+        let cost = &self.blueprint.robot_costs[robot];
+        let mut wait = 0;
+        for resource in [ORE, CLAY, OBSIDIAN] {
+            if cost[resource] <= self.resources[resource] {
+                continue;
+            }
+            if self.robots[resource] == 0 {
+                return None;
+            }
+            let missing = cost[resource] - self.resources[resource];
+            wait = usize::max(wait, missing.div_ceil(self.robots[resource]));
+        }
+        Some(wait)
+    }
+
+    /// Skips the time until the robot is affordable and builds it
+    fn build(&self, robot: usize, wait: usize) -> Factory<'a> {
+        let elapsed = wait + 1;
+        let mut next = self.clone();
+        next.time_left -= elapsed;
+        for resource in [ORE, CLAY, OBSIDIAN, GEODE] {
+            next.resources[resource] += self.robots[resource] * elapsed;
+            next.resources[resource] -= self.blueprint.robot_costs[robot][resource];
+        }
+        next.robots[robot] += 1;
+        next
+    }
+}
+
+impl SearchState for Factory<'_> {
+    fn value(&self) -> usize {
+        self.resources[GEODE] + self.robots[GEODE] * self.time_left
+    }
+
+    fn upper_bound(&self) -> usize {
+        // Relaxed simulation: ore and clay are free and a new obsidian robot is built
+        // every minute, alongside a geode robot whenever there is enough obsidian for it.
+        let geode_robot_obsidian = self.blueprint.robot_costs[GEODE][OBSIDIAN];
+        let mut obsidian = self.resources[OBSIDIAN];
+        let mut geodes = self.resources[GEODE];
+        let mut geode_robots = self.robots[GEODE];
+        for obsidian_robots in (self.robots[OBSIDIAN]..).take(self.time_left) {
+            let can_build_geode_robot = obsidian >= geode_robot_obsidian;
+            if can_build_geode_robot {
+                obsidian -= geode_robot_obsidian;
+            }
+            obsidian += obsidian_robots;
+            geodes += geode_robots;
+            if can_build_geode_robot {
+                geode_robots += 1;
+            }
+        }
+        geodes
+    }
+
+    fn successors(&self) -> Vec<Self> {
+        let max_useful_robots = self.blueprint.max_useful_robots();
+        [GEODE, OBSIDIAN, CLAY, ORE]
+            .into_iter()
+            .filter(|&robot| self.robots[robot] < max_useful_robots[robot])
+            .filter_map(|robot| {
+                self.wait_time(robot)
+                    .filter(|&wait| wait < self.time_left)
+                    .map(|wait| self.build(robot, wait))
+            })
+            .collect()
+    }
+}
+
+pub fn max_geodes(blueprint: &Blueprint, time_budget: usize) -> usize {
+    maximize(Factory::new(blueprint, time_budget))
+}
@@ -0,0 +1,42 @@
+/*! See https://adventofcode.com/2022/day/20 */
+
+#![allow(non_upper_case_globals)]
+
+use day20::{grove_coordinates_sum, mix, parse_numbers};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn mix_and_sum_coordinates(reader: impl Read) -> i64 {
+    let numbers = parse_numbers(reader);
+    grove_coordinates_sum(&mix(&numbers, 1))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day20/input.txt")?;
+    let coordinates_sum = mix_and_sum_coordinates(asset.data.as_ref());
+    println!("Sum of grove coordinates: {coordinates_sum}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(mix_and_sum_coordinates(asset.data.as_ref()), 3);
+    }
+
+    #[test]
+    fn test_input_mixed_order() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        let numbers = parse_numbers(asset.data.as_ref());
+        assert_eq!(mix(&numbers, 1), vec![-2, 1, 2, -3, 4, 0, 3]);
+    }
+}
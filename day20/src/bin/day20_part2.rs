@@ -0,0 +1,41 @@
+/*! See https://adventofcode.com/2022/day/20 */
+
+#![allow(non_upper_case_globals)]
+
+use day20::{grove_coordinates_sum, mix, parse_numbers, DECRYPTION_KEY};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn decrypt_and_sum_coordinates(reader: impl Read, decryption_key: i64, rounds: usize) -> i64 {
+    let numbers: Vec<i64> = parse_numbers(reader)
+        .into_iter()
+        .map(|n| n * decryption_key)
+        .collect();
+    grove_coordinates_sum(&mix(&numbers, rounds))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day20/input.txt")?;
+    let coordinates_sum = decrypt_and_sum_coordinates(asset.data.as_ref(), DECRYPTION_KEY, 10);
+    println!("Sum of decrypted grove coordinates: {coordinates_sum}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(
+            decrypt_and_sum_coordinates(asset.data.as_ref(), DECRYPTION_KEY, 10),
+            1623178306
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::io::{BufRead, BufReader, Read};
+
+pub const DECRYPTION_KEY: i64 = 811589153;
+
+pub fn parse_numbers(reader: impl Read) -> Vec<i64> {
+    BufReader::new(reader)
+        .lines()
+        .map(|line| line.unwrap())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.trim().parse().unwrap())
+        .collect()
+}
+
+/// Ordered list of ids `0..len` split into blocks of roughly `sqrt(len)` elements
+/// (sqrt decomposition), so that finding, removing and inserting an element
+/// costs `O(sqrt(n))` instead of `O(n)` for a plain [Vec].
+pub struct MixingList {
+    blocks: Vec<Vec<usize>>,
+    /// Index of the block every id currently belongs to
+    block_of: Vec<usize>,
+    block_size: usize,
+    /// Number of ids currently in the list
+    len: usize,
+}
+
+impl MixingList {
+    /// Creates a list of ids `0..len` in their initial order
+    pub fn new(len: usize) -> Self {
+        let block_size = usize::max(1, (len as f64).sqrt() as usize);
+        let mut list = MixingList {
+            blocks: vec![(0..len).collect()],
+            block_of: vec![0; len],
+            block_size,
+            len,
+        };
+        list.rebuild();
+        list
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Redistributes all ids evenly between the blocks
+    fn rebuild(&mut self) {
+        let ids: Vec<usize> = self.blocks.concat();
+        self.blocks = ids
+            .chunks(self.block_size)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        if self.blocks.is_empty() {
+            self.blocks.push(vec![]);
+        }
+        for (block_idx, block) in self.blocks.iter().enumerate() {
+            for &id in block {
+                self.block_of[id] = block_idx;
+            }
+        }
+    }
+
+    /// Finds the block containing `pos` and the offset of `pos` within it
+    fn locate(&self, mut pos: usize) -> (usize, usize) {
+        for (block_idx, block) in self.blocks.iter().enumerate() {
+            if pos < block.len() {
+                return (block_idx, pos);
+            }
+            pos -= block.len();
+        }
+        // position right after the very last element
+        let last = self.blocks.len() - 1;
+        (last, self.blocks[last].len() + pos)
+    }
+
+    pub fn position_of(&self, id: usize) -> usize {
+        let block_idx = self.block_of[id];
+        let preceding: usize = self.blocks[..block_idx].iter().map(Vec::len).sum();
+        let offset = self.blocks[block_idx]
+            .iter()
+            .position(|&other| other == id)
+            .unwrap();
+        preceding + offset
+    }
+
+    pub fn get(&self, pos: usize) -> usize {
+        let (block_idx, offset) = self.locate(pos);
+        self.blocks[block_idx][offset]
+    }
+
+    pub fn remove_at(&mut self, pos: usize) -> usize {
+        let (block_idx, offset) = self.locate(pos);
+        self.len -= 1;
+        self.blocks[block_idx].remove(offset)
+    }
+
+    pub fn insert_at(&mut self, pos: usize, id: usize) {
+        let (block_idx, offset) = self.locate(pos);
+        self.blocks[block_idx].insert(offset, id);
+        self.block_of[id] = block_idx;
+        self.len += 1;
+        if self.blocks[block_idx].len() > 2 * self.block_size {
+            self.rebuild();
+        }
+    }
+
+    /// Moves the element by `shift` positions, wrapping around the circular list.
+    /// A list of a single element has nowhere to move it
+    pub fn shift(&mut self, id: usize, shift: i64) {
+        if self.len() < 2 {
+            return;
+        }
+        let pos = self.position_of(id);
+        self.remove_at(pos);
+        // the element itself is not part of the circle while it moves
+        let new_pos = (pos as i64 + shift).rem_euclid(self.len() as i64) as usize;
+        self.insert_at(new_pos, id);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().flatten().copied()
+    }
+}
+
+/// Moves every number by its own value, in the original order, `rounds` times.
+/// Returns the numbers in their resulting order.
+pub fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    let mut list = MixingList::new(numbers.len());
+    for _ in 0..rounds {
+        for (id, &number) in numbers.iter().enumerate() {
+            list.shift(id, number);
+        }
+    }
+    list.iter().map(|id| numbers[id]).collect()
+}
+
+/// Sums 1000th, 2000th and 3000th numbers after the value `0`, wrapping around
+pub fn grove_coordinates_sum(mixed: &[i64]) -> i64 {
+    let zero_pos = mixed.iter().position(|&n| n == 0).unwrap();
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| mixed[(zero_pos + offset) % mixed.len()])
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+        let mut ids: Vec<usize> = (0..numbers.len()).collect();
+        for _ in 0..rounds {
+            for (id, &number) in numbers.iter().enumerate() {
+                let pos = ids.iter().position(|&other| other == id).unwrap();
+                ids.remove(pos);
+                let new_pos = (pos as i64 + number).rem_euclid(ids.len() as i64) as usize;
+                ids.insert(new_pos, id);
+            }
+        }
+        ids.into_iter().map(|id| numbers[id]).collect()
+    }
+
+    #[test]
+    fn mix_same_as_naive() {
+        // simple LCG, good enough to get some pseudo-random numbers
+        let mut seed: i64 = 42;
+        let mut numbers = vec![0];
+        for _ in 0..500 {
+            seed = (seed * 1103515245 + 12345) % 2147483648;
+            numbers.push(seed % 2001 - 1000);
+        }
+        assert_eq!(mix(&numbers, 3), naive_mix(&numbers, 3));
+    }
+
+    #[test]
+    fn list_operations() {
+        let mut list = MixingList::new(10);
+        assert_eq!(list.remove_at(3), 3);
+        assert_eq!(list.len(), 9);
+        assert_eq!(list.len(), list.iter().count());
+        list.insert_at(9, 3);
+        assert_eq!(list.len(), 10);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            [0, 1, 2, 4, 5, 6, 7, 8, 9, 3]
+        );
+        assert_eq!(list.position_of(3), 9);
+        assert_eq!(list.get(3), 4);
+
+        let mut list = MixingList::new(1);
+        list.shift(0, 5);
+        assert_eq!(list.iter().collect::<Vec<_>>(), [0]);
+        assert_eq!(mix(&[7], 2), [7]);
+    }
+}
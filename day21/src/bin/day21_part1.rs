@@ -0,0 +1,34 @@
+/*! See https://adventofcode.com/2022/day/21 */
+
+#![allow(non_upper_case_globals)]
+
+use day21::{Monkeys, ROOT};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn root_yells(reader: impl Read) -> i64 {
+    Monkeys::parse(reader).evaluate(ROOT)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day21/input.txt")?;
+    let root_number = root_yells(asset.data.as_ref());
+    println!("Root monkey yells: {root_number}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(root_yells(asset.data.as_ref()), 152);
+    }
+}
@@ -0,0 +1,36 @@
+/*! See https://adventofcode.com/2022/day/21 */
+
+#![allow(non_upper_case_globals)]
+
+use day21::{Division, Monkeys, HUMAN, ROOT};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn human_has_to_yell(reader: impl Read) -> Option<i64> {
+    // with truncating division the example is solved by both 301 and 302
+    Monkeys::parse(reader).solve_equality(HUMAN, ROOT, Division::Exact)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day21/input.txt")?;
+    let human_number = human_has_to_yell(asset.data.as_ref())
+        .ok_or("No number makes both operands of root equal")?;
+    println!("Human has to yell: {human_number}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(human_has_to_yell(asset.data.as_ref()), Some(301));
+    }
+}
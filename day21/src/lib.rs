@@ -0,0 +1,304 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, BufReader, Read},
+};
+
+pub use day11::{BinaryOperator, Operation};
+
+pub const ROOT: &str = "root";
+pub const HUMAN: &str = "humn";
+
+/// How a division is undone while solving for one of its operands
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Division {
+    /// `/` truncates, so only a unique operand counts as a solution
+    Truncating,
+    /// `/` has to leave no remainder, as it does in the puzzle inputs
+    Exact,
+}
+
+pub enum Job {
+    Number(i64),
+    /// Operands are the names of other monkeys
+    Operation(Operation<String>),
+}
+
+impl Job {
+    /// Parses either `5` or `pppw + sjmn`
+    pub fn parse(job_str: &str) -> Self {
+        let parts: Vec<_> = job_str.split(' ').collect();
+        match parts.len() {
+            1 => Job::Number(str::parse(parts[0]).unwrap()),
+            3 => Job::Operation(Operation {
+                left_operand: parts[0].to_owned(),
+                right_operand: parts[2].to_owned(),
+                binary_operator: BinaryOperator::parse(parts[1]),
+            }),
+            _ => panic!("Expected either a number or a binary operation, but found: {job_str}"),
+        }
+    }
+}
+
+pub struct Monkeys {
+    pub jobs: HashMap<String, Job>,
+}
+
+impl Monkeys {
+    /// Parses files of strings like:
+    /// root: pppw + sjmn
+    /// dbpl: 5
+    pub fn parse(reader: impl Read) -> Monkeys {
+        let mut jobs = HashMap::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line.unwrap();
+            let (name, job_str) = line
+                .split_once(": ")
+                .unwrap_or_else(|| panic!("Expected `name: job`, but found: {line}"));
+            jobs.insert(name.to_owned(), Job::parse(job_str));
+        }
+        Monkeys { jobs }
+    }
+
+    fn job(&self, name: &str) -> &Job {
+        self.jobs
+            .get(name)
+            .unwrap_or_else(|| panic!("Unknown monkey: {name}"))
+    }
+
+    pub fn evaluate(&self, name: &str) -> i64 {
+        self.evaluate_with(name, &mut HashMap::new())
+    }
+
+    /// Evaluates `name`, remembering the value of every monkey on the way,
+    /// so that expressions shared by several monkeys are computed only once
+    fn evaluate_with<'a>(&'a self, name: &'a str, values: &mut HashMap<&'a str, i64>) -> i64 {
+        if let Some(&value) = values.get(name) {
+            return value;
+        }
+        let value = match self.job(name) {
+            Job::Number(number) => *number,
+            Job::Operation(operation) => {
+                let left = self.evaluate_with(&operation.left_operand, values);
+                let right = self.evaluate_with(&operation.right_operand, values);
+                operation.binary_operator.apply(left, right)
+            }
+        };
+        values.insert(name, value);
+        value
+    }
+
+    /// Names of all monkeys whose value depends on the value yelled by `target`,
+    /// including `target` itself
+    pub fn dependents_of<'a>(&'a self, target: &'a str) -> HashSet<&'a str> {
+        let mut known = HashMap::new();
+        known.insert(target, true);
+        for name in self.jobs.keys() {
+            self.depends_with(name, &mut known);
+        }
+        known
+            .into_iter()
+            .filter(|&(_, depends)| depends)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn depends_with<'a>(&'a self, name: &'a str, known: &mut HashMap<&'a str, bool>) -> bool {
+        if let Some(&depends) = known.get(name) {
+            return depends;
+        }
+        let depends = match self.job(name) {
+            Job::Number(_) => false,
+            Job::Operation(operation) => {
+                // both sides are visited so that every monkey gets memoized
+                let left = self.depends_with(&operation.left_operand, known);
+                let right = self.depends_with(&operation.right_operand, known);
+                left || right
+            }
+        };
+        known.insert(name, depends);
+        depends
+    }
+
+    /// Whether the value yelled by `name` depends on the value yelled by `target`
+    pub fn depends_on(&self, name: &str, target: &str) -> bool {
+        self.dependents_of(target).contains(name)
+    }
+
+    /// Finds what `unknown` has to yell so that `name` yells `expected`.
+    ///
+    /// Walks down the path from `name` to `unknown`: on every step the other branch
+    /// does not depend on the unknown, so it is evaluated and the operation is inverted.
+    /// Returns `None` if `name` does not depend on `unknown` or no unique integer
+    /// solves an inverted operation, with `/` truncating.
+    pub fn solve_for(&self, unknown: &str, name: &str, expected: i64) -> Option<i64> {
+        let dependents = self.dependents_of(unknown);
+        self.solve_along(
+            unknown,
+            name,
+            expected,
+            Division::Truncating,
+            &dependents,
+            &mut HashMap::new(),
+        )
+    }
+
+    fn solve_along<'a>(
+        &'a self,
+        unknown: &str,
+        mut name: &'a str,
+        mut expected: i64,
+        division: Division,
+        dependents: &HashSet<&str>,
+        values: &mut HashMap<&'a str, i64>,
+    ) -> Option<i64> {
+        if !dependents.contains(name) {
+            return None;
+        }
+        while name != unknown {
+            // every dependent monkey but the unknown itself has an operation
+            let Job::Operation(operation) = self.job(name) else {
+                return None;
+            };
+            let (left, right) = (&operation.left_operand, &operation.right_operand);
+            let operator = operation.binary_operator;
+            if dependents.contains(left.as_str()) {
+                let right_value = self.evaluate_with(right, values);
+                expected = match division {
+                    Division::Truncating => operator.solve_for_left(expected, right_value),
+                    Division::Exact => operator.solve_exactly_for_left(expected, right_value),
+                }?;
+                name = left;
+            } else {
+                let left_value = self.evaluate_with(left, values);
+                expected = match division {
+                    Division::Truncating => operator.solve_for_right(left_value, expected),
+                    Division::Exact => operator.solve_exactly_for_right(left_value, expected),
+                }?;
+                name = right;
+            }
+        }
+        Some(expected)
+    }
+
+    /// Treats the operation of `name` as an equality check and finds what `unknown`
+    /// has to yell so that both operands are equal.
+    pub fn solve_equality(&self, unknown: &str, name: &str, division: Division) -> Option<i64> {
+        let operation = match self.job(name) {
+            Job::Number(_) => panic!("Monkey `{name}` is expected to have an operation"),
+            Job::Operation(operation) => operation,
+        };
+        let dependents = self.dependents_of(unknown);
+        let mut values = HashMap::new();
+        let (left, right) = (&operation.left_operand, &operation.right_operand);
+        if dependents.contains(left.as_str()) {
+            let expected = self.evaluate_with(right, &mut values);
+            self.solve_along(unknown, left, expected, division, &dependents, &mut values)
+        } else {
+            let expected = self.evaluate_with(left, &mut values);
+            self.solve_along(unknown, right, expected, division, &dependents, &mut values)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_every_operator() {
+        for op in ["+", "-", "*", "/"] {
+            let monkeys = Monkeys::parse(
+                format!("root: humn {op} cnst\ncnst: 4\nhumn: 0\nleft: cnst {op} humn").as_bytes(),
+            );
+            // `humn / 4 == 5` holds for 20 to 23, so it has no unique solution
+            let expected = if op == "/" { None } else { Some(20) };
+            assert_eq!(
+                monkeys.solve_for(HUMAN, ROOT, BinaryOperator::parse(op).apply(20, 4)),
+                expected
+            );
+            assert_eq!(
+                monkeys.solve_for(HUMAN, "left", BinaryOperator::parse(op).apply(4, 2)),
+                Some(2)
+            );
+        }
+    }
+
+    #[test]
+    fn inexact_solutions() {
+        let monkeys = Monkeys::parse(
+            "root: humn * two
+two: 2
+humn: 0
+left: two / humn"
+                .as_bytes(),
+        );
+        assert_eq!(monkeys.solve_for(HUMAN, ROOT, 5), None);
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 0), None);
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 3), None);
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 1), Some(2));
+        assert_eq!(monkeys.solve_for(HUMAN, "two", 2), None);
+
+        let monkeys = Monkeys::parse(
+            "root: humn / zero
+zero: 0
+humn: 0"
+                .as_bytes(),
+        );
+        assert_eq!(monkeys.solve_for(HUMAN, ROOT, 0), None);
+
+        let monkeys = Monkeys::parse(
+            "root: humn / two
+two: 2
+one: 1
+zero: 0
+minus: zero - one
+humn: 0
+sev: 7
+left: sev / humn
+exact: humn / one
+flip: humn / minus"
+                .as_bytes(),
+        );
+        // x / 2 == 5 holds for both 10 and 11
+        assert_eq!(monkeys.solve_for(HUMAN, ROOT, 5), None);
+        assert_eq!(monkeys.solve_for(HUMAN, "exact", 5), Some(5));
+        assert_eq!(monkeys.solve_for(HUMAN, "flip", 5), Some(-5));
+        // 7 / x == 2 only holds for 3
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 2), Some(3));
+        assert_eq!(monkeys.solve_for(HUMAN, "left", -2), Some(-3));
+        // 7 / x == 1 holds for 4 to 7
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 1), None);
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 4), None);
+        assert_eq!(monkeys.solve_for(HUMAN, "left", 7), Some(1));
+
+        let monkeys = Monkeys::parse(
+            "root: half + five\nhalf: humn / two\ntwo: 2\nfive: 5\nhumn: 0".as_bytes(),
+        );
+        assert_eq!(
+            monkeys.solve_equality(HUMAN, ROOT, Division::Truncating),
+            None
+        );
+        assert_eq!(
+            monkeys.solve_equality(HUMAN, ROOT, Division::Exact),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn shared_subexpressions() {
+        // every level refers to the previous one twice, so without memoization
+        // this would take 2^60 evaluations
+        let mut text = String::from("m0: one + one\none: 1\nhumn: 1\n");
+        for level in 1..=60 {
+            let prev = level - 1;
+            text.push_str(&format!("m{level}: m{prev} - m{prev}\n"));
+        }
+        text.push_str("root: humn + m60\n");
+        let monkeys = Monkeys::parse(text.as_bytes());
+        assert_eq!(monkeys.evaluate(ROOT), 1);
+        assert!(monkeys.depends_on(ROOT, HUMAN));
+        assert!(!monkeys.depends_on("m60", HUMAN));
+        assert_eq!(monkeys.dependents_of(HUMAN), HashSet::from([HUMAN, ROOT]));
+        assert_eq!(monkeys.solve_for(HUMAN, ROOT, 7), Some(7));
+    }
+}
@@ -0,0 +1,36 @@
+/*! See https://adventofcode.com/2022/day/22 */
+
+#![allow(non_upper_case_globals)]
+
+use day22::{parse_notes, password, walk, CubeTopology};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn final_cube_password(reader: impl Read) -> usize {
+    let (board, path) = parse_notes(reader);
+    let (pos, direction) = walk(&board, &path, &CubeTopology::new(&board));
+    password(&pos, direction)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day22/input.txt")?;
+    let password = final_cube_password(asset.data.as_ref());
+    println!("Final password: {password}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(final_cube_password(asset.data.as_ref()), 5031);
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{BufRead, BufReader, Read},
+};
+
+/// `x` is the column and `y` is the row, both 0-based
+pub use day09::Pos;
+
+/// Variants are ordered by their facing value: `Right` is 0, `Down` is 1, etc.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Direction {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
+
+    pub fn facing(&self) -> usize {
+        *self as usize
+    }
+
+    /// `(x, y)` delta of a single step
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Up => (0, -1),
+        }
+    }
+
+    /// Position a single step away from `pos`
+    pub fn step(&self, pos: Pos) -> Pos {
+        let (dx, dy) = self.delta();
+        pos.offset(dx, dy)
+    }
+
+    pub fn turn(&self, turn: Turn) -> Direction {
+        let shift = match turn {
+            Turn::Clockwise => 1,
+            Turn::Counterclockwise => 3,
+        };
+        Direction::ALL[(self.facing() + shift) % 4]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(self.facing() + 2) % 4]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Turn {
+    Clockwise,
+    Counterclockwise,
+}
+
+impl From<&str> for Turn {
+    fn from(value: &str) -> Self {
+        match value {
+            "R" => Turn::Clockwise,
+            "L" => Turn::Counterclockwise,
+            _ => panic!("Unsupported turn: {value}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Instruction {
+    Forward(usize),
+    Turn(Turn),
+}
+
+/// Parses a path like `10R5L5R10L4R5L5`
+pub fn parse_path(path: &str) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    let mut steps = String::new();
+    for c in path.trim().chars() {
+        if c.is_ascii_digit() {
+            steps.push(c);
+            continue;
+        }
+        if !steps.is_empty() {
+            instructions.push(Instruction::Forward(steps.parse().unwrap()));
+            steps.clear();
+        }
+        instructions.push(Instruction::Turn(c.to_string().as_str().into()));
+    }
+    if !steps.is_empty() {
+        instructions.push(Instruction::Forward(steps.parse().unwrap()));
+    }
+    instructions
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Tile {
+    Open,
+    Wall,
+}
+
+/// Sparse board: positions outside of the map are simply missing
+pub struct Board {
+    pub tiles: HashMap<Pos, Tile>,
+}
+
+impl Board {
+    pub fn parse(lines: &[String]) -> Board {
+        let mut tiles = HashMap::new();
+        for (row, line) in lines.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let tile = match c {
+                    '.' => Tile::Open,
+                    '#' => Tile::Wall,
+                    ' ' => continue,
+                    _ => panic!("Unexpected board tile: '{c}'"),
+                };
+                tiles.insert(Pos::new(col as i32, row as i32), tile);
+            }
+        }
+        Board { tiles }
+    }
+
+    pub fn get(&self, pos: &Pos) -> Option<Tile> {
+        self.tiles.get(pos).copied()
+    }
+
+    /// The leftmost open tile of the top row
+    pub fn start(&self) -> Pos {
+        *self
+            .tiles
+            .iter()
+            .filter(|(_, &tile)| tile == Tile::Open)
+            .map(|(pos, _)| pos)
+            .min_by_key(|pos| (pos.y, pos.x))
+            .unwrap()
+    }
+}
+
+/// Parses the board followed by an empty line and the path
+pub fn parse_notes(reader: impl Read) -> (Board, Vec<Instruction>) {
+    let lines: Vec<String> = BufReader::new(reader)
+        .lines()
+        .map(|line| line.unwrap())
+        .collect();
+    let separator = lines
+        .iter()
+        .position(|line| line.is_empty())
+        .expect("Board and path are expected to be separated with an empty line");
+    (
+        Board::parse(&lines[..separator]),
+        parse_path(&lines[separator + 1..].concat()),
+    )
+}
+
+/// Defines where a walker ends up after stepping off the edge of the board
+pub trait Topology {
+    fn wrap(&self, board: &Board, pos: Pos, direction: Direction) -> (Pos, Direction);
+}
+
+/// Walking off an edge leads to the opposite edge of the same row or column
+pub struct FlatTopology;
+
+impl Topology for FlatTopology {
+    fn wrap(&self, board: &Board, pos: Pos, direction: Direction) -> (Pos, Direction) {
+        let back = direction.opposite();
+        let mut wrapped = pos;
+        while board.get(&back.step(wrapped)).is_some() {
+            wrapped = back.step(wrapped);
+        }
+        (wrapped, direction)
+    }
+}
+
+type Vec3 = [i32; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Orientation of a cube face: where its "right" and "down" screen directions point to
+/// and the outward normal of the face, all in 3D.
+#[derive(Debug, Clone, Copy)]
+struct FaceFrame {
+    right: Vec3,
+    down: Vec3,
+    normal: Vec3,
+}
+
+impl FaceFrame {
+    fn axis(&self, direction: Direction) -> Vec3 {
+        match direction {
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => neg(self.right),
+            Direction::Up => neg(self.down),
+        }
+    }
+
+    /// Orientation of the neighbouring face of the net after folding it over the edge:
+    /// the neighbour's normal becomes the direction of movement, while walking on
+    /// the neighbour in the same screen direction goes along the old inward normal.
+    fn fold(&self, direction: Direction) -> FaceFrame {
+        let FaceFrame {
+            right,
+            down,
+            normal,
+        } = *self;
+        match direction {
+            Direction::Right => FaceFrame {
+                right: neg(normal),
+                down,
+                normal: right,
+            },
+            Direction::Left => FaceFrame {
+                right: normal,
+                down,
+                normal: neg(right),
+            },
+            Direction::Down => FaceFrame {
+                right,
+                down: neg(normal),
+                normal: down,
+            },
+            Direction::Up => FaceFrame {
+                right,
+                down: normal,
+                normal: neg(down),
+            },
+        }
+    }
+}
+
+/// The board is folded into a cube, walking off an edge leads to the adjacent face.
+///
+/// The edges are stitched automatically for any net: every face of the net is given
+/// its 3D orientation by folding the net face by face, starting from the first one.
+/// Tile centers are then mapped to 3D points (in doubled coordinates, so the cube
+/// spans `-size..=size` on every axis) and back.
+pub struct CubeTopology {
+    size: i32,
+    /// Face position on the net (in units of face size) to its orientation
+    frames: HashMap<(i32, i32), FaceFrame>,
+}
+
+impl CubeTopology {
+    pub fn new(board: &Board) -> Self {
+        let size = ((board.tiles.len() / 6) as f64).sqrt() as i32;
+        assert_eq!(
+            (size * size * 6) as usize,
+            board.tiles.len(),
+            "The board is not a net of a cube"
+        );
+
+        let face_of = |pos: &Pos| (pos.y.div_euclid(size), pos.x.div_euclid(size));
+        let start_face = face_of(&board.start());
+
+        let mut frames = HashMap::new();
+        let start_frame = FaceFrame {
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+            normal: [0, 0, -1],
+        };
+        frames.insert(start_face, start_frame);
+        let mut queue = VecDeque::from([start_face]);
+        while let Some(face) = queue.pop_front() {
+            let frame = frames[&face];
+            for direction in Direction::ALL {
+                let (dx, dy) = direction.delta();
+                let neighbour = (face.0 + dy, face.1 + dx);
+                let corner = Pos::new(neighbour.1 * size, neighbour.0 * size);
+                if frames.contains_key(&neighbour) || board.get(&corner).is_none() {
+                    continue;
+                }
+                frames.insert(neighbour, frame.fold(direction));
+                queue.push_back(neighbour);
+            }
+        }
+        assert_eq!(frames.len(), 6, "The board is not a net of a cube");
+
+        CubeTopology { size, frames }
+    }
+}
+
+impl Topology for CubeTopology {
+    fn wrap(&self, _board: &Board, pos: Pos, direction: Direction) -> (Pos, Direction) {
+        let size = self.size;
+        let face = (pos.y.div_euclid(size), pos.x.div_euclid(size));
+        let frame = self.frames[&face];
+
+        // 3D point of the tile center
+        let u = 2 * (pos.x - face.1 * size) - (size - 1);
+        let v = 2 * (pos.y - face.0 * size) - (size - 1);
+        let point: Vec3 =
+            [0, 1, 2].map(|i| frame.normal[i] * size + frame.right[i] * u + frame.down[i] * v);
+
+        // Stepping over the edge moves the point along the direction of movement
+        // and "down" the old face normal, onto the adjacent face.
+        let axis = frame.axis(direction);
+        let next_point: Vec3 = [0, 1, 2].map(|i| point[i] + axis[i] - frame.normal[i]);
+
+        let (&next_face, next_frame) = self
+            .frames
+            .iter()
+            .find(|(_, next_frame)| next_frame.normal == axis)
+            .unwrap();
+        let next_col = (dot(next_point, next_frame.right) + size - 1) / 2;
+        let next_row = (dot(next_point, next_frame.down) + size - 1) / 2;
+        let next_direction = *Direction::ALL
+            .iter()
+            .find(|&&d| next_frame.axis(d) == neg(frame.normal))
+            .unwrap();
+
+        (
+            Pos::new(next_face.1 * size + next_col, next_face.0 * size + next_row),
+            next_direction,
+        )
+    }
+}
+
+/// Follows the path and returns the final position and direction
+pub fn walk(board: &Board, path: &[Instruction], topology: &impl Topology) -> (Pos, Direction) {
+    let mut pos = board.start();
+    let mut direction = Direction::Right;
+    for instruction in path {
+        match *instruction {
+            Instruction::Turn(turn) => direction = direction.turn(turn),
+            Instruction::Forward(steps) => {
+                for _ in 0..steps {
+                    let (next_pos, next_direction) = match board.get(&direction.step(pos)) {
+                        Some(_) => (direction.step(pos), direction),
+                        None => topology.wrap(board, pos, direction),
+                    };
+                    if board.get(&next_pos) == Some(Tile::Wall) {
+                        break;
+                    }
+                    pos = next_pos;
+                    direction = next_direction;
+                }
+            }
+        }
+    }
+    (pos, direction)
+}
+
+pub fn password(pos: &Pos, direction: Direction) -> usize {
+    1000 * (pos.y as usize + 1) + 4 * (pos.x as usize + 1) + direction.facing()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an open board from a net drawn with one char per face
+    fn open_board(net: &[&str], size: i32) -> Board {
+        let mut tiles = HashMap::new();
+        for (face_row, line) in net.iter().enumerate() {
+            for (face_col, c) in line.chars().enumerate() {
+                if c == ' ' {
+                    continue;
+                }
+                for row in 0..size {
+                    for col in 0..size {
+                        let pos =
+                            Pos::new(face_col as i32 * size + col, face_row as i32 * size + row);
+                        tiles.insert(pos, Tile::Open);
+                    }
+                }
+            }
+        }
+        Board { tiles }
+    }
+
+    #[test]
+    fn walking_around_the_cube_returns_back() {
+        let nets: [&[&str]; 4] = [
+            &["  #", "###", "  ##"],
+            &[" ##", " #", "##", "#"],
+            &[" #", "####", " #"],
+            &["##", " ###", "   #"],
+        ];
+        let size = 3;
+        for net in nets {
+            let board = open_board(net, size);
+            let cube = CubeTopology::new(&board);
+            for &start in board.tiles.keys() {
+                for direction in Direction::ALL {
+                    let mut pos = start;
+                    let mut dir = direction;
+                    for _ in 0..4 * size {
+                        (pos, dir) = match board.get(&dir.step(pos)) {
+                            Some(_) => (dir.step(pos), dir),
+                            None => cube.wrap(&board, pos, dir),
+                        };
+                        assert!(board.get(&pos).is_some());
+                    }
+                    assert_eq!((pos, dir), (start, direction), "net: {net:?}");
+                }
+            }
+        }
+    }
+}
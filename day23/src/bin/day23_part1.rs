@@ -0,0 +1,38 @@
+/*! See https://adventofcode.com/2022/day/23 */
+
+#![allow(non_upper_case_globals)]
+
+use day23::Grove;
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn empty_ground_after(reader: impl Read, rounds: usize) -> usize {
+    let mut grove = Grove::parse(reader);
+    for _ in 0..rounds {
+        grove.step();
+    }
+    grove.empty_ground()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day23/input.txt")?;
+    let empty_ground = empty_ground_after(asset.data.as_ref(), 10);
+    println!("Empty ground tiles after 10 rounds: {empty_ground}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(empty_ground_after(asset.data.as_ref(), 10), 110);
+    }
+}
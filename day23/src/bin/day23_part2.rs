@@ -0,0 +1,36 @@
+/*! See https://adventofcode.com/2022/day/23 */
+
+#![allow(non_upper_case_globals)]
+
+use day23::Grove;
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn first_round_without_moves(reader: impl Read) -> usize {
+    let mut grove = Grove::parse(reader);
+    while grove.step() > 0 {}
+    grove.rounds
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day23/input.txt")?;
+    let round = first_round_without_moves(asset.data.as_ref());
+    println!("First round where no elf moves: {round}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(first_round_without_moves(asset.data.as_ref()), 20);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::{BufRead, BufReader, Read},
+};
+
+pub use day09::Pos;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    /// Initial order in which the directions are considered
+    pub const PRIORITY: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+    ];
+
+    pub fn step(&self, pos: &Pos) -> Pos {
+        match self {
+            Direction::North => pos.offset(0, -1),
+            Direction::South => pos.offset(0, 1),
+            Direction::West => pos.offset(-1, 0),
+            Direction::East => pos.offset(1, 0),
+        }
+    }
+
+    /// Positions that must be free to move this way: the target one and its two diagonals
+    pub fn scanned(&self, pos: &Pos) -> [Pos; 3] {
+        let target = self.step(pos);
+        match self {
+            Direction::North | Direction::South => {
+                [target.offset(-1, 0), target, target.offset(1, 0)]
+            }
+            Direction::West | Direction::East => {
+                [target.offset(0, -1), target, target.offset(0, 1)]
+            }
+        }
+    }
+}
+
+pub struct Grove {
+    pub elves: HashSet<Pos>,
+    pub rounds: usize,
+}
+
+impl Grove {
+    /// Parses a scan where `#` is an elf and `.` is empty ground
+    pub fn parse(reader: impl Read) -> Grove {
+        let mut elves = HashSet::new();
+        for (y, line) in BufReader::new(reader).lines().enumerate() {
+            for (x, c) in line.unwrap().chars().enumerate() {
+                if c == '#' {
+                    elves.insert(Pos::new(x as i32, y as i32));
+                }
+            }
+        }
+        Grove { elves, rounds: 0 }
+    }
+
+    /// Directions in the order they are considered during the current round
+    pub fn directions(&self) -> impl Iterator<Item = Direction> {
+        let first = self.rounds % Direction::PRIORITY.len();
+        Direction::PRIORITY
+            .into_iter()
+            .cycle()
+            .skip(first)
+            .take(Direction::PRIORITY.len())
+    }
+
+    fn propose(&self, elf: &Pos) -> Option<Pos> {
+        if elf.adjacent().all(|pos| !self.elves.contains(&pos)) {
+            return None;
+        }
+        self.directions()
+            .find(|direction| {
+                direction
+                    .scanned(elf)
+                    .iter()
+                    .all(|pos| !self.elves.contains(pos))
+            })
+            .map(|direction| direction.step(elf))
+    }
+
+    /// Runs a single round. Returns the number of elves that moved.
+    pub fn step(&mut self) -> usize {
+        let mut proposals: HashMap<Pos, Vec<Pos>> = HashMap::new();
+        for elf in self.elves.iter() {
+            if let Some(target) = self.propose(elf) {
+                proposals.entry(target).or_default().push(*elf);
+            }
+        }
+
+        let mut moved = 0;
+        for (target, candidates) in proposals {
+            // elves proposing the same position do not move at all
+            if candidates.len() == 1 {
+                self.elves.remove(&candidates[0]);
+                self.elves.insert(target);
+                moved += 1;
+            }
+        }
+        self.rounds += 1;
+        moved
+    }
+
+    /// Returns the top-left and bottom-right corners of the smallest rectangle containing all elves
+    pub fn bounds(&self) -> (Pos, Pos) {
+        let min_x = self.elves.iter().map(|pos| pos.x).min().unwrap_or_default();
+        let max_x = self.elves.iter().map(|pos| pos.x).max().unwrap_or_default();
+        let min_y = self.elves.iter().map(|pos| pos.y).min().unwrap_or_default();
+        let max_y = self.elves.iter().map(|pos| pos.y).max().unwrap_or_default();
+        (Pos::new(min_x, min_y), Pos::new(max_x, max_y))
+    }
+
+    /// Counts empty ground tiles within the bounding rectangle
+    pub fn empty_ground(&self) -> usize {
+        if self.elves.is_empty() {
+            return 0;
+        }
+        let (top_left, bottom_right) = self.bounds();
+        let area = (bottom_right.x - top_left.x + 1) * (bottom_right.y - top_left.y + 1);
+        area as usize - self.elves.len()
+    }
+}
+
+impl Display for Grove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (top_left, bottom_right) = self.bounds();
+        for y in top_left.y..=bottom_right.y {
+            for x in top_left.x..=bottom_right.x {
+                let c = if self.elves.contains(&Pos::new(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_example() {
+        let mut grove = Grove::parse(".....\n..##.\n..#..\n.....\n..##.\n.....".as_bytes());
+        assert_eq!(grove.to_string(), "##\n#.\n..\n##\n");
+
+        assert_eq!(grove.step(), 3);
+        assert_eq!(grove.to_string(), "##\n..\n#.\n.#\n#.\n");
+
+        grove.step();
+        grove.step();
+        assert_eq!(
+            grove.to_string(),
+            "..#..\n....#\n#....\n....#\n.....\n..#..\n"
+        );
+        assert_eq!(grove.step(), 0);
+    }
+}
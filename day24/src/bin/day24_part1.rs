@@ -0,0 +1,35 @@
+/*! See https://adventofcode.com/2022/day/24 */
+
+#![allow(non_upper_case_globals)]
+
+use day24::{shortest_path, Valley};
+use rust_embed::RustEmbed;
+use std::io::Read;
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn fewest_minutes_to_goal(reader: impl Read) -> usize {
+    let valley = Valley::parse(reader);
+    shortest_path(&valley, valley.start, valley.end, 0).expect("No path to the goal")
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day24/input.txt")?;
+    let minutes = fewest_minutes_to_goal(asset.data.as_ref());
+    println!("Fewest minutes to reach the goal: {minutes}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        assert_eq!(fewest_minutes_to_goal(asset.data.as_ref()), 18);
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    collections::HashSet,
+    io::{BufRead, BufReader, Read},
+};
+
+/// Position on the map, including the surrounding walls: row `0` is the top wall
+pub use day12::Point;
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+pub struct Valley {
+    /// Inner width and height, i.e. without walls
+    pub width: usize,
+    pub height: usize,
+    pub start: Point,
+    pub end: Point,
+    /// Blizzards repeat their positions every `period` minutes
+    pub period: usize,
+    /// `blocked[t][row][col]` is set if there is a blizzard at (inner) `row` and `col`
+    /// at minute `t`, for every `t` within a single period.
+    blocked: Vec<Vec<Vec<bool>>>,
+}
+
+impl Valley {
+    /// Parses a map like:
+    /// #.######
+    /// #>>.<^<#
+    /// #.<..<<#
+    /// #>v.><>#
+    /// #<^v^^>#
+    /// ######.#
+    pub fn parse(reader: impl Read) -> Valley {
+        let lines: Vec<Vec<char>> = BufReader::new(reader)
+            .lines()
+            .map(|line| line.unwrap().chars().collect())
+            .filter(|line: &Vec<char>| !line.is_empty())
+            .collect();
+        let height = lines.len() - 2;
+        let width = lines[0].len() - 2;
+        let start = Point::new(0, lines[0].iter().position(|&c| c == '.').unwrap());
+        let end = Point::new(
+            height + 1,
+            lines[height + 1].iter().position(|&c| c == '.').unwrap(),
+        );
+
+        // (row, col, row delta, col delta), inner coordinates
+        let mut blizzards: Vec<(usize, usize, isize, isize)> = vec![];
+        for (row, line) in lines[1..=height].iter().enumerate() {
+            for (col, &c) in line[1..=width].iter().enumerate() {
+                let (d_row, d_col) = match c {
+                    '>' => (0, 1),
+                    '<' => (0, -1),
+                    'v' => (1, 0),
+                    '^' => (-1, 0),
+                    _ => continue,
+                };
+                blizzards.push((row, col, d_row, d_col));
+            }
+        }
+
+        let period = lcm(width, height);
+        let mut blocked = vec![vec![vec![false; width]; height]; period];
+        for (t, blocked_at_t) in blocked.iter_mut().enumerate() {
+            for &(row, col, d_row, d_col) in blizzards.iter() {
+                let row = (row as isize + d_row * t as isize).rem_euclid(height as isize);
+                let col = (col as isize + d_col * t as isize).rem_euclid(width as isize);
+                blocked_at_t[row as usize][col as usize] = true;
+            }
+        }
+
+        Valley {
+            width,
+            height,
+            start,
+            end,
+            period,
+            blocked,
+        }
+    }
+
+    /// Whether the point is free of walls and blizzards at the given minute
+    pub fn is_free(&self, p: &Point, time: usize) -> bool {
+        if *p == self.start || *p == self.end {
+            return true;
+        }
+        if p.row == 0 || p.row > self.height || p.col == 0 || p.col > self.width {
+            return false;
+        }
+        !self.blocked[time % self.period][p.row - 1][p.col - 1]
+    }
+
+    /// Neighbouring points, plus the point itself for waiting in place
+    pub fn get_moves(&self, p: &Point) -> Vec<Point> {
+        let mut result = vec![*p];
+        if p.col > 0 {
+            result.push(Point::new(p.row, p.col - 1));
+        }
+        result.push(Point::new(p.row, p.col + 1));
+        if p.row > 0 {
+            result.push(Point::new(p.row - 1, p.col));
+        }
+        result.push(Point::new(p.row + 1, p.col));
+        result
+    }
+}
+
+/// Finds the minute of the earliest arrival to `end`, when leaving `start` at `start_time`.
+///
+/// Same level-by-level BFS as in `day12::shortest_path`, except that the graph changes
+/// every minute: the nodes are `(point, time % period)`, since blizzards repeat themselves.
+pub fn shortest_path(
+    valley: &Valley,
+    start: Point,
+    end: Point,
+    start_time: usize,
+) -> Option<usize> {
+    let mut time = start_time;
+    let mut visited: HashSet<(Point, usize)> = HashSet::new();
+    let mut next_queue = vec![start];
+    let mut queue;
+
+    loop {
+        queue = next_queue;
+        next_queue = vec![];
+
+        for p in queue {
+            if p == end {
+                return Some(time);
+            }
+            for n in valley.get_moves(&p) {
+                if valley.is_free(&n, time + 1) && visited.insert((n, (time + 1) % valley.period)) {
+                    next_queue.push(n);
+                }
+            }
+        }
+        time += 1;
+
+        if next_queue.is_empty() {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_valley_blizzards_wrap() {
+        let valley = Valley::parse("#.#####\n#.....#\n#>....#\n#.....#\n#####.#".as_bytes());
+        assert_eq!(valley.period, 15);
+        assert!(!valley.is_free(&Point::new(2, 1), 0));
+        assert!(!valley.is_free(&Point::new(2, 5), 4));
+        assert!(!valley.is_free(&Point::new(2, 1), 5));
+        assert!(valley.is_free(&Point::new(2, 1), 4));
+    }
+}
@@ -0,0 +1,39 @@
+/*! See https://adventofcode.com/2022/day/25 */
+
+#![allow(non_upper_case_globals)]
+
+use day25::Snafu;
+use rust_embed::RustEmbed;
+use std::io::{BufRead, BufReader, Read};
+
+#[derive(RustEmbed)]
+#[folder = "."]
+struct Asset;
+
+fn sum_of_fuel_requirements(reader: impl Read) -> Snafu {
+    BufReader::new(reader)
+        .lines()
+        .map(|line| line.unwrap().parse::<Snafu>().unwrap())
+        .sum()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let asset = Asset::get("input.txt")
+        .ok_or("input.txt is missing, save your puzzle input as day25/input.txt")?;
+    let sum = sum_of_fuel_requirements(asset.data.as_ref());
+    println!("SNAFU number to supply to Bob's console: {sum}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input() {
+        let asset = Asset::get("test_input.txt").unwrap();
+        let sum = sum_of_fuel_requirements(asset.data.as_ref());
+        assert_eq!(sum.to_string(), "2=-1=0");
+        assert_eq!(i64::try_from(&sum), Ok(4890));
+    }
+}
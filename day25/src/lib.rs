@@ -0,0 +1,247 @@
+use std::{fmt::Display, iter::Sum, ops::Add, str::FromStr};
+
+/// Number in balanced base 5: digits are `=` (-2), `-` (-1), `0`, `1` and `2`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Snafu {
+    /// Least significant digit first, without leading zeros, so zero has no digits at all
+    digits: Vec<i8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnafuError {
+    Empty,
+    InvalidDigit(char),
+    Overflow,
+}
+
+impl Display for SnafuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnafuError::Empty => write!(f, "SNAFU number cannot be empty"),
+            SnafuError::InvalidDigit(c) => write!(f, "Invalid SNAFU digit: '{c}'"),
+            SnafuError::Overflow => write!(f, "SNAFU number does not fit into the target type"),
+        }
+    }
+}
+
+impl std::error::Error for SnafuError {}
+
+/// Splits `value` into a balanced digit in `-2..=2` and a carry, so that `value == digit + 5 * carry`
+fn balance(value: i128) -> (i8, i128) {
+    let remainder = value.rem_euclid(5);
+    let quotient = value.div_euclid(5);
+    if remainder > 2 {
+        ((remainder - 5) as i8, quotient + 1)
+    } else {
+        (remainder as i8, quotient)
+    }
+}
+
+impl Snafu {
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    fn trim(mut self) -> Self {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+}
+
+impl FromStr for Snafu {
+    type Err = SnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(SnafuError::Empty);
+        }
+        let digits = s
+            .chars()
+            .rev()
+            .map(|c| match c {
+                '=' => Ok(-2),
+                '-' => Ok(-1),
+                '0' => Ok(0),
+                '1' => Ok(1),
+                '2' => Ok(2),
+                _ => Err(SnafuError::InvalidDigit(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Snafu { digits }.trim())
+    }
+}
+
+impl Display for Snafu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        for digit in self.digits.iter().rev() {
+            let c = match digit {
+                -2 => '=',
+                -1 => '-',
+                0 => '0',
+                1 => '1',
+                2 => '2',
+                _ => unreachable!("Unexpected SNAFU digit: {digit}"),
+            };
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<i128> for Snafu {
+    fn from(mut value: i128) -> Self {
+        let mut digits = vec![];
+        while value != 0 {
+            let (digit, carry) = balance(value);
+            digits.push(digit);
+            value = carry;
+        }
+        Snafu { digits }
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(value: i64) -> Self {
+        Snafu::from(value as i128)
+    }
+}
+
+impl TryFrom<&Snafu> for i128 {
+    type Error = SnafuError;
+
+    /// Accumulates the most significant digit first. `acc * 5 + digit` is computed as
+    /// `acc * 4 + (acc + digit)`, so that e.g. `i128::MIN` does not overflow on `acc * 5`
+    /// before the opposite-signed low digit is added
+    fn try_from(value: &Snafu) -> Result<Self, Self::Error> {
+        value.digits.iter().rev().try_fold(0i128, |acc, &digit| {
+            acc.checked_mul(4)
+                .zip(acc.checked_add(digit as i128))
+                .and_then(|(high, low)| high.checked_add(low))
+                .ok_or(SnafuError::Overflow)
+        })
+    }
+}
+
+impl TryFrom<&Snafu> for i64 {
+    type Error = SnafuError;
+
+    fn try_from(value: &Snafu) -> Result<Self, Self::Error> {
+        i64::try_from(i128::try_from(value)?).map_err(|_| SnafuError::Overflow)
+    }
+}
+
+impl Add for &Snafu {
+    type Output = Snafu;
+
+    /// Adds digit by digit with a carry, without converting to decimal
+    fn add(self, rhs: Self) -> Self::Output {
+        let len = usize::max(self.digits.len(), rhs.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for i in 0..len {
+            let a = self.digits.get(i).copied().unwrap_or(0);
+            let b = rhs.digits.get(i).copied().unwrap_or(0);
+            let (digit, next_carry) = balance((a + b) as i128 + carry);
+            digits.push(digit);
+            carry = next_carry;
+        }
+        if carry != 0 {
+            digits.push(carry as i8);
+        }
+        Snafu { digits }.trim()
+    }
+}
+
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, n| &acc + &n)
+    }
+}
+
+impl<'a> Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, n| &acc + n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn examples() {
+        for (decimal, snafu) in [
+            (0, "0"),
+            (1, "1"),
+            (3, "1="),
+            (8, "2="),
+            (10, "20"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ] {
+            assert_eq!(Snafu::from(decimal).to_string(), snafu);
+            assert_eq!(i64::try_from(&snafu.parse::<Snafu>().unwrap()), Ok(decimal));
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Snafu>(), Err(SnafuError::Empty));
+        assert_eq!("1=3".parse::<Snafu>(), Err(SnafuError::InvalidDigit('3')));
+        assert_eq!("00".parse::<Snafu>().unwrap().to_string(), "0");
+        let too_big = Snafu::from(i128::from(i64::MAX) + 1);
+        assert_eq!(i64::try_from(&too_big), Err(SnafuError::Overflow));
+        let too_big = &Snafu::from(i128::MAX) + &Snafu::from(1i128);
+        assert_eq!(i128::try_from(&too_big), Err(SnafuError::Overflow));
+        let too_small = &Snafu::from(i128::MIN) + &Snafu::from(-1i128);
+        assert_eq!(i128::try_from(&too_small), Err(SnafuError::Overflow));
+    }
+
+    #[test]
+    fn i128_extremes() {
+        for value in [i128::MIN, i128::MIN + 1, i128::MAX, i128::MAX - 1] {
+            assert_eq!(i128::try_from(&Snafu::from(value)), Ok(value));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn i128_round_trip(value in any::<i128>()) {
+            prop_assert_eq!(i128::try_from(&Snafu::from(value)), Ok(value));
+        }
+
+        #[test]
+        fn string_round_trip(value in any::<i64>()) {
+            let snafu = Snafu::from(value);
+            prop_assert_eq!(snafu.to_string().parse::<Snafu>(), Ok(snafu));
+        }
+
+        #[test]
+        fn add_matches_decimal(a in any::<i64>(), b in any::<i64>()) {
+            let sum = &Snafu::from(a) + &Snafu::from(b);
+            prop_assert_eq!(i128::try_from(&sum), Ok(a as i128 + b as i128));
+        }
+
+        #[test]
+        fn sum_matches_decimal(values in prop::collection::vec(any::<i64>(), 0..20)) {
+            let sum: Snafu = values.iter().map(|&v| Snafu::from(v)).sum();
+            prop_assert_eq!(i128::try_from(&sum), Ok(values.iter().map(|&v| v as i128).sum()));
+        }
+    }
+}